@@ -6,14 +6,101 @@ export interface PublicKey {
     x: Buffer;
     y: Buffer;
 }
+/**
+ * The compressed form of a PublicKey, as created by `compressPublicKey`. Every function which takes a PublicKey argument
+ * also accepts the compressed form, as do the public key fields of EncryptedValues and TransformKeys.
+ */
+export type CompressedPublicKey = Buffer;
 export interface KeyPair {
     publicKey: PublicKey;
     privateKey: PrivateKey;
 }
+/**
+ * An opaque handle to a 256-bit private key which is held in native memory and zeroized when the handle is garbage
+ * collected. The key bytes are only exposed by `privateKeyHandleToBytes`. Every Api256 function which takes a private
+ * key accepts a handle.
+ */
+export interface PrivateKeyHandle {
+    readonly __privateKeyHandle: unique symbol;
+}
+export interface HandleKeyPair {
+    publicKey: PublicKey;
+    privateKey: PrivateKeyHandle;
+}
+export interface GenerateKeyPairOptions {
+    /**
+     * Return the private key as a PrivateKeyHandle instead of a Buffer.
+     */
+    handle?: boolean;
+}
+export interface UserKeyRotation {
+    privateKey: PrivateKey;
+    augmentationFactor: PrivateKey;
+    publicKey: PublicKey;
+}
+export interface HandleUserKeyRotation {
+    privateKey: PrivateKeyHandle;
+    augmentationFactor: PrivateKeyHandle;
+    publicKey: PublicKey;
+}
+export interface GroupKeyRotation extends UserKeyRotation {
+    /**
+     * The transform key from the new group private key to the member at the same index, or the error which occurred for
+     * that member.
+     */
+    transformKeys: Array<TransformKey | RecryptError>;
+}
+export interface HandleGroupKeyRotation extends HandleUserKeyRotation {
+    transformKeys: Array<TransformKey | RecryptError>;
+}
+export interface AugmentedKeyPair {
+    /**
+     * The server's half of the combined key, which must be kept to augment transform keys and verify signatures.
+     */
+    augmentationPrivateKey: PrivateKey;
+    /**
+     * The user's public key augmented with the augmentation public key. Values should be encrypted to this key.
+     */
+    combinedPublicKey: PublicKey;
+}
+export interface HandleAugmentedKeyPair {
+    augmentationPrivateKey: PrivateKeyHandle;
+    combinedPublicKey: PublicKey;
+}
+export interface EncryptedValueVerification {
+    valid: boolean;
+    /**
+     * Set when `valid` is false. `PublicSigningKeyMismatch` if the value wasn't signed with the expected key, or
+     * `InvalidEncryptedMessageSignature` if the signature doesn't match the content.
+     */
+    code?: "PublicSigningKeyMismatch" | "InvalidEncryptedMessageSignature";
+    /**
+     * Set when `valid` is false, to the path of the field which failed the check.
+     */
+    field?: string;
+}
 export interface SigningKeyPair {
     publicKey: PublicSigningKey;
     privateKey: PrivateSigningKey;
 }
+/**
+ * An opaque handle to an Ed25519 signing key pair which is held in native memory and zeroized when the handle is garbage
+ * collected. The key bytes are only exposed by `signingKeyPairHandleToBytes`. Every function which takes a private
+ * signing key accepts a handle, on both Api256 and Api480.
+ */
+export interface SigningKeyPairHandle {
+    readonly __signingKeyPairHandle: unique symbol;
+}
+export interface HandleSigningKeyPair {
+    publicKey: PublicSigningKey;
+    privateKey: SigningKeyPairHandle;
+}
+export interface GenerateEd25519KeyPairOptions {
+    /**
+     * Return the private key as a SigningKeyPairHandle instead of a Buffer.
+     */
+    handle?: boolean;
+}
 export type Plaintext = Buffer;
 export interface TransformBlock {
     publicKey: PublicKey;
@@ -39,25 +126,489 @@ export interface TransformKey {
     signature: Buffer;
 }
 
-export function augmentPublicKey256(publicKey: PublicKey, otherPublicKey: PublicKey): PublicKey;
-export function augmentTransformKey256(transformKey: TransformKey, privateKey: PrivateKey): TransformKey;
+export interface EncryptedData {
+    /**
+     * The Plaintext whose derived symmetric key encrypted the data, encrypted to the recipient's public key.
+     */
+    encryptedDek: EncryptedValue;
+    /**
+     * The 12 byte AES-GCM nonce, followed by the encrypted data and the 16 byte GCM tag.
+     */
+    ciphertext: Buffer;
+}
+
+/**
+ * Data encrypted once for many recipients. Each recipient id maps to the Plaintext whose derived symmetric key encrypted
+ * the data, encrypted to that recipient's public key.
+ */
+export interface SharedDocument {
+    ciphertext: Buffer;
+    /**
+     * Has a null prototype, so any string, including `__proto__`, can be used as a recipient id.
+     */
+    encryptedDeks: {[recipientId: string]: EncryptedValue};
+}
+
+/**
+ * The metadata in the header of a document in the IronCore document format, which other IronCore SDKs can read.
+ */
+export interface DocumentHeader {
+    documentId: string;
+    segmentId: number;
+}
+
+export interface DecryptedDocument {
+    header: DocumentHeader;
+    data: Buffer;
+}
+
+/**
+ * Encrypts data into the chunked streaming format. Each call to `update` returns the encrypted bytes which are ready,
+ * which may be empty, and `final` returns the rest of the stream. Wrap it in a Node Transform stream to pipe data
+ * through it.
+ */
+export interface StreamEncryptor {
+    update(chunk: Buffer): Buffer;
+    final(): Buffer;
+}
+
+/**
+ * Decrypts a stream created by a StreamEncryptor. `update` only returns data which has been authenticated. `final`
+ * throws an error with code `InvalidCiphertext` if the stream was truncated, reordered or modified, and the decryptor
+ * can't be used again after any error.
+ */
+export interface StreamDecryptor {
+    update(chunk: Buffer): Buffer;
+    final(): Buffer;
+}
+
+/**
+ * Argon2id parameters used to stretch a passphrase. Defaults to 19456 KiB of memory, 2 iterations and a parallelism of 1.
+ * At most 256 MiB of memory, 10 iterations and a parallelism of 16 are allowed, including in the blobs being imported.
+ */
+export interface KdfParams {
+    memoryCost?: number;
+    timeCost?: number;
+    parallelism?: number;
+}
+
+export interface ImportPrivateKeyOptions {
+    /**
+     * Return the private key as a PrivateKeyHandle or SigningKeyPairHandle instead of a Buffer.
+     */
+    handle?: boolean;
+}
+
+export type ImportedPrivateKey<P, S> =
+    | {keyType: "recrypt"; privateKey: P; publicKey: PublicKey}
+    | {keyType: "ed25519"; privateKey: S; publicKey: PublicSigningKey};
+
+export type RecryptErrorCode =
+    | "MissingField"
+    | "InvalidFieldType"
+    | "InvalidBufferLength"
+    | "InvalidPublicKey"
+    | "InvalidHashedValue"
+    | "InvalidSigningKeyPair"
+    | "EmptyArray"
+    | "DecryptFailed"
+    | "InvalidEncryptedMessageSignature"
+    | "InvalidTransformKey"
+    | "InputWrongSize"
+    | "DecodeFailure"
+    | "UnsupportedVersion"
+    | "InvalidEncoding"
+    | "InvalidCiphertext"
+    | "StreamFinished"
+    | "UnknownRecipient"
+    | "InvalidMnemonic"
+    | "InvalidChecksum"
+    | "PublicKeyMismatch"
+    | "NotEnoughShares"
+    | "PublicSigningKeyMismatch";
+
+/**
+ * Errors thrown by this library. TypeErrors are thrown for missing or mistyped fields and RangeErrors are thrown for
+ * Buffers of the wrong length. `field` is the full path to the offending field, e.g.
+ * `encryptedValue.transformBlocks[1].publicKey.y`, and is only set for errors caused by a specific argument.
+ */
+export interface RecryptError extends Error {
+    code: RecryptErrorCode;
+    field?: string;
+}
+
+export interface Api256Options {
+    /**
+     * FOR TESTS ONLY. A 32 byte seed for a ChaCha20 CSPRNG which is used instead of OS randomness, so that every key pair,
+     * plaintext and encrypted value generated by the instance is reproducible. Never use a seeded instance for real data.
+     */
+    seed?: Buffer;
+}
+
+export interface BatchOptions {
+    /**
+     * Spread the work across all CPU cores.
+     */
+    parallel?: boolean;
+}
+
+export interface DecryptManyOptions extends BatchOptions {
+    /**
+     * Return the symmetric key derived from each decrypted plaintext instead of the plaintext itself.
+     */
+    deriveSymmetricKey?: boolean;
+}
+
+export interface RotateGroupKeyOptions extends BatchOptions, GenerateKeyPairOptions {}
+
+export function augmentPublicKey256(publicKey: PublicKey | CompressedPublicKey, otherPublicKey: PublicKey | CompressedPublicKey): PublicKey;
+export function augmentTransformKey256(transformKey: TransformKey, privateKey: PrivateKey | PrivateKeyHandle): TransformKey;
+/**
+ * Augment each of a user's outgoing transform keys with the augmentation factor from `Api256.rotateUserPrivateKey`, so
+ * they transform from the rotated private key. Throws if any of the transform keys is invalid.
+ */
+export function applyRotationToTransformKeys(transformKeys: TransformKey[], augmentationFactor: PrivateKey | PrivateKeyHandle): TransformKey[];
 export function transformKeyToBytes256(transformKey: TransformKey): Buffer;
+/**
+ * Compress a PublicKey into 33 bytes: a prefix byte which records whether y is odd, followed by x.
+ */
+export function compressPublicKey(publicKey: PublicKey): CompressedPublicKey;
+/**
+ * Recover a PublicKey from its compressed form. Throws an error with code `InvalidPublicKey` if it isn't a valid point.
+ */
+export function decompressPublicKey(compressedPublicKey: CompressedPublicKey): PublicKey;
+/**
+ * Serialize an EncryptedValue into a compact, versioned binary layout which is safe to store. The layout is documented in
+ * `src/serialization.rs`.
+ */
+export function encryptedValueToBytes(encryptedValue: EncryptedValue): Buffer;
+/**
+ * Parse an EncryptedValue out of bytes created by `encryptedValueToBytes`. Throws an error with code `UnsupportedVersion`
+ * for unknown versions, and a RangeError with code `InvalidBufferLength` for truncated input or trailing bytes.
+ */
+export function encryptedValueFromBytes(bytes: Buffer): EncryptedValue;
+/**
+ * Serialize all six fields of a TransformKey into a versioned binary layout which can be stored and parsed back with
+ * `transformKeyFromBytes`. This is not the same as `transformKeyToBytes256`, which only produces the bytes that are signed.
+ */
+export function transformKeyToBytes(transformKey: TransformKey): Buffer;
+/**
+ * Parse a TransformKey out of bytes created by `transformKeyToBytes`. The public keys and hashed temp key are validated.
+ */
+export function transformKeyFromBytes(bytes: Buffer): TransformKey;
+/**
+ * Read the header of a document in the IronCore document format without decrypting it, e.g. to find the key needed to
+ * decrypt it. Throws an error with code `UnsupportedVersion` if the bytes aren't a version 2 IronCore document, or
+ * `InvalidEncoding` if the header can't be parsed.
+ */
+export function parseDocumentHeader(encryptedDocument: Buffer): DocumentHeader;
+/**
+ * Export the bytes of the private key held by a PrivateKeyHandle.
+ */
+export function privateKeyHandleToBytes(privateKey: PrivateKeyHandle): PrivateKey;
+/**
+ * Import private key bytes into a new PrivateKeyHandle. Clear the Buffer afterwards if it shouldn't stay on the JS heap.
+ */
+export function privateKeyHandleFromBytes(privateKey: PrivateKey): PrivateKeyHandle;
+/**
+ * Add two private keys. The result is a PrivateKeyHandle if either argument is.
+ */
+/**
+ * Export the 64 bytes of the signing key pair held by a SigningKeyPairHandle.
+ */
+export function signingKeyPairHandleToBytes(privateSigningKey: SigningKeyPairHandle): PrivateSigningKey;
+/**
+ * Validate a 64 byte private signing key once and import it into a new SigningKeyPairHandle.
+ */
+export function signingKeyPairHandleFromBytes(privateSigningKey: PrivateSigningKey): SigningKeyPairHandle;
 export function addPrivateKeys(privateKeyA: PrivateKey, privateKeyB: PrivateKey): PrivateKey;
+export function addPrivateKeys(privateKeyA: PrivateKey | PrivateKeyHandle, privateKeyB: PrivateKey | PrivateKeyHandle): PrivateKeyHandle;
+/**
+ * Subtract the second private key from the first. The result is a PrivateKeyHandle if either argument is.
+ */
 export function subtractPrivateKeys(privateKeyA: PrivateKey, privateKeyB: PrivateKey): PrivateKey;
+export function subtractPrivateKeys(privateKeyA: PrivateKey | PrivateKeyHandle, privateKeyB: PrivateKey | PrivateKeyHandle): PrivateKeyHandle;
+export function augmentPublicKey480(publicKey: PublicKey | CompressedPublicKey, otherPublicKey: PublicKey | CompressedPublicKey): PublicKey;
+export function augmentTransformKey480(transformKey: TransformKey, privateKey: PrivateKey): TransformKey;
+export function transformKeyToBytes480(transformKey: TransformKey): Buffer;
+export function compressPublicKey480(publicKey: PublicKey): CompressedPublicKey;
+export function decompressPublicKey480(compressedPublicKey: CompressedPublicKey): PublicKey;
+export function addPrivateKeys480(privateKeyA: PrivateKey, privateKeyB: PrivateKey): PrivateKey;
+export function subtractPrivateKeys480(privateKeyA: PrivateKey, privateKeyB: PrivateKey): PrivateKey;
 export class Api256 {
+    constructor(options?: Api256Options);
+    /**
+     * Whether this instance was created with a seed. Production code should refuse to use deterministic instances.
+     */
+    isDeterministic(): boolean;
+    generateKeyPair(options?: GenerateKeyPairOptions & {handle?: false}): KeyPair;
+    generateKeyPair(options: GenerateKeyPairOptions & {handle: true}): HandleKeyPair;
+    /**
+     * Deterministically derive a key pair from a passphrase. The passphrase is stretched with Argon2id using the salt, which
+     * must be at least 8 bytes, so the same passphrase, salt and parameters always give the same key pair.
+     */
+    deriveKeyPairFromPassphrase(passphrase: string | Buffer, salt: Buffer, kdfParams?: KdfParams, options?: GenerateKeyPairOptions & {handle?: false}): KeyPair;
+    deriveKeyPairFromPassphrase(passphrase: string | Buffer, salt: Buffer, kdfParams: KdfParams | undefined, options: GenerateKeyPairOptions & {handle: true}): HandleKeyPair;
+    deriveKeyPairFromPassphraseAsync(
+        passphrase: string | Buffer,
+        salt: Buffer,
+        kdfParams?: KdfParams,
+        options?: GenerateKeyPairOptions & {handle?: false}
+    ): Promise<KeyPair>;
+    deriveKeyPairFromPassphraseAsync(
+        passphrase: string | Buffer,
+        salt: Buffer,
+        kdfParams: KdfParams | undefined,
+        options: GenerateKeyPairOptions & {handle: true}
+    ): Promise<HandleKeyPair>;
+    /**
+     * Encode the private key as 24 space separated words from the BIP39 English wordlist, including a checksum.
+     */
+    privateKeyToMnemonic(privateKey: PrivateKey | PrivateKeyHandle): string;
+    /**
+     * Decode a mnemonic created by `privateKeyToMnemonic`, either as one string or an array of words. Throws an error with
+     * code `InvalidMnemonic` for unknown words, `InvalidChecksum` if the words were mistyped, and `PublicKeyMismatch` if
+     * the key doesn't produce `expectedPublicKey`.
+     */
+    mnemonicToPrivateKey(words: string | string[], expectedPublicKey?: PublicKey | CompressedPublicKey, options?: GenerateKeyPairOptions & {handle?: false}): KeyPair;
+    mnemonicToPrivateKey(words: string | string[], expectedPublicKey: PublicKey | CompressedPublicKey | undefined, options: GenerateKeyPairOptions & {handle: true}): HandleKeyPair;
+    /**
+     * Split the private key into `shares` Shamir shares, any `threshold` of which recover it. Both must be integers from 2
+     * to 255. Each share is a Buffer which records its index, the threshold, the public key and a checksum.
+     */
+    splitPrivateKey(privateKey: PrivateKey | PrivateKeyHandle, threshold: number, shares: number): Buffer[];
+    /**
+     * Combine at least `threshold` shares from `splitPrivateKey` back into the key pair. Throws an error with code
+     * `InvalidChecksum` for a corrupted share, `NotEnoughShares` if too few were provided, and `PublicKeyMismatch` if the
+     * shares are from different splits or the key doesn't produce `expectedPublicKey`. The recovered private key is reduced
+     * modulo the curve order, so it may differ from the original bytes but always has the same public key.
+     */
+    combinePrivateKeyShares(shares: Buffer[], expectedPublicKey?: PublicKey | CompressedPublicKey, options?: GenerateKeyPairOptions & {handle?: false}): KeyPair;
+    combinePrivateKeyShares(shares: Buffer[], expectedPublicKey: PublicKey | CompressedPublicKey | undefined, options: GenerateKeyPairOptions & {handle: true}): HandleKeyPair;
+    /**
+     * Rotate a user's private key by adding a random augmentation factor to it. The new public key is checked to be the
+     * current public key augmented with the factor's public key. Pass the factor to `applyRotationToTransformKeys` to
+     * update the user's outgoing transform keys.
+     */
+    rotateUserPrivateKey(currentPrivateKey: PrivateKey | PrivateKeyHandle, options?: GenerateKeyPairOptions & {handle?: false}): UserKeyRotation;
+    rotateUserPrivateKey(currentPrivateKey: PrivateKey | PrivateKeyHandle, options: GenerateKeyPairOptions & {handle: true}): HandleUserKeyRotation;
+    /**
+     * Rotate a group's private key the same way as `rotateUserPrivateKey` and generate a fresh transform key from the new
+     * group private key to each member's public key in one call. A failure for one member doesn't fail the rotation.
+     */
+    rotateGroupKey(
+        groupPrivateKey: PrivateKey | PrivateKeyHandle,
+        memberPublicKeys: Array<PublicKey | CompressedPublicKey>,
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle,
+        options?: RotateGroupKeyOptions & {handle?: false}
+    ): GroupKeyRotation;
+    rotateGroupKey(
+        groupPrivateKey: PrivateKey | PrivateKeyHandle,
+        memberPublicKeys: Array<PublicKey | CompressedPublicKey>,
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle,
+        options: RotateGroupKeyOptions & {handle: true}
+    ): HandleGroupKeyRotation;
+    rotateGroupKeyAsync(
+        groupPrivateKey: PrivateKey | PrivateKeyHandle,
+        memberPublicKeys: Array<PublicKey | CompressedPublicKey>,
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle,
+        options?: RotateGroupKeyOptions & {handle?: false}
+    ): Promise<GroupKeyRotation>;
+    rotateGroupKeyAsync(
+        groupPrivateKey: PrivateKey | PrivateKeyHandle,
+        memberPublicKeys: Array<PublicKey | CompressedPublicKey>,
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle,
+        options: RotateGroupKeyOptions & {handle: true}
+    ): Promise<HandleGroupKeyRotation>;
+    generateEd25519KeyPair(options?: GenerateEd25519KeyPairOptions & {handle?: false}): SigningKeyPair;
+    generateEd25519KeyPair(options: GenerateEd25519KeyPairOptions & {handle: true}): HandleSigningKeyPair;
+    ed25519Sign(privateKey: PrivateSigningKey | SigningKeyPairHandle, message: Buffer): Signature;
+    ed25519Verify(publicKey: PublicSigningKey, message: Buffer, signature: Signature): boolean;
+    /**
+     * Check the Ed25519 signature over an EncryptedValue or TransformedValue, including its transform blocks, without
+     * decrypting it. If `expectedPublicSigningKey` is provided the value must also have been signed with it. Malformed
+     * values still throw.
+     */
+    verifyEncryptedValue(encryptedValue: EncryptedValue, expectedPublicSigningKey?: PublicSigningKey): EncryptedValueVerification;
+    computeEd25519PublicKey(privateKey: PrivateSigningKey | SigningKeyPairHandle): PublicSigningKey;
+    generatePlaintext(): Plaintext;
+    generateTransformKey(fromPrivateKey: PrivateKey | PrivateKeyHandle, toPublicKey: PublicKey | CompressedPublicKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): TransformKey;
+    generateTransformKeyAsync(fromPrivateKey: PrivateKey | PrivateKeyHandle, toPublicKey: PublicKey | CompressedPublicKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): Promise<TransformKey>;
+    computePublicKey(privateKey: PrivateKey | PrivateKeyHandle): PublicKey;
+    deriveSymmetricKey(plaintext: Plaintext): Buffer;
+    /**
+     * Derive a private key by hashing the provided 384 byte secret, such as a Plaintext. The same secret always gives the same key.
+     */
+    derivePrivateKey(secretBytes: Plaintext, options?: GenerateKeyPairOptions & {handle?: false}): PrivateKey;
+    derivePrivateKey(secretBytes: Plaintext, options: GenerateKeyPairOptions & {handle: true}): PrivateKeyHandle;
+    encrypt(plaintext: Plaintext, toPublicKey: PublicKey | CompressedPublicKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): EncryptedValue;
+    /**
+     * Encrypt the plaintext to each of the provided public keys. Each entry in the result is either the EncryptedValue for the
+     * public key at the same index or the error which occurred for that recipient.
+     */
+    encryptMany(plaintext: Plaintext, toPublicKeys: Array<PublicKey | CompressedPublicKey>, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): Array<EncryptedValue | RecryptError>;
+    encryptAsync(plaintext: Plaintext, toPublicKey: PublicKey | CompressedPublicKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): Promise<EncryptedValue>;
+    transform(encryptedValue: EncryptedValue, transformKey: TransformKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): EncryptedValue;
+    /**
+     * Transform each of the encrypted values with the same transform key. Each entry in the result is either the transformed
+     * EncryptedValue for the value at the same index or the error which occurred for that value.
+     */
+    transformMany(
+        encryptedValues: EncryptedValue[],
+        transformKey: TransformKey,
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle,
+        options?: BatchOptions
+    ): Array<EncryptedValue | RecryptError>;
+    transformAsync(encryptedValue: EncryptedValue, transformKey: TransformKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): Promise<EncryptedValue>;
+    decrypt(encryptedValue: EncryptedValue, privateKey: PrivateKey | PrivateKeyHandle): Plaintext;
+    decryptAsync(encryptedValue: EncryptedValue, privateKey: PrivateKey | PrivateKeyHandle): Promise<Plaintext>;
+    /**
+     * Decrypt each of the encrypted values with the same private key. Each entry in the result is either the Plaintext (or
+     * derived symmetric key) for the value at the same index or the error which occurred for that value.
+     */
+    decryptMany(encryptedValues: EncryptedValue[], privateKey: PrivateKey | PrivateKeyHandle, options?: DecryptManyOptions): Array<Buffer | RecryptError>;
+    decryptManyAsync(encryptedValues: EncryptedValue[], privateKey: PrivateKey | PrivateKeyHandle, options?: DecryptManyOptions): Promise<Array<Buffer | RecryptError>>;
+    /**
+     * Encrypt the data with AES-256-GCM under the symmetric key derived from a newly generated Plaintext, and encrypt that
+     * Plaintext to the public key. The optional AAD is authenticated but not stored, so the same AAD must be passed to
+     * `decryptData`.
+     */
+    encryptData(data: Buffer, toPublicKey: PublicKey | CompressedPublicKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle, aad?: Buffer): EncryptedData;
+    /**
+     * Decrypt data encrypted by `encryptData`. Throws an error with code `InvalidCiphertext` if the ciphertext was modified
+     * or the AAD doesn't match.
+     */
+    decryptData(envelope: EncryptedData, privateKey: PrivateKey | PrivateKeyHandle, aad?: Buffer): Buffer;
+    /**
+     * Encrypt the data once with AES-256-GCM under the symmetric key derived from a newly generated Plaintext, and encrypt
+     * that Plaintext to the public key of every recipient. The AAD works the same as for `encryptData`.
+     */
+    createSharedDocument(
+        data: Buffer,
+        recipients: {[recipientId: string]: PublicKey | CompressedPublicKey},
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle,
+        aad?: Buffer
+    ): SharedDocument;
+    /**
+     * Decrypt a shared document as one of its recipients. Throws an error with code `UnknownRecipient` if the recipient
+     * doesn't have access.
+     */
+    decryptSharedDocument(document: SharedDocument, recipientId: string, privateKey: PrivateKey | PrivateKeyHandle, aad?: Buffer): Buffer;
+    /**
+     * Return a copy of the document which the recipient can also decrypt. The author's private key must be able to decrypt
+     * one of the document's encrypted DEKs. The data isn't re-encrypted.
+     */
+    grantAccess(
+        document: SharedDocument,
+        recipientId: string,
+        publicKey: PublicKey | CompressedPublicKey,
+        authorPrivateKey: PrivateKey | PrivateKeyHandle,
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle
+    ): SharedDocument;
+    /**
+     * Return a copy of the document without the recipient's encrypted DEK. The data isn't re-encrypted, so a recipient who
+     * kept the DEK can still decrypt it.
+     */
+    revokeAccess(document: SharedDocument, recipientId: string): SharedDocument;
+    /**
+     * Encrypt the data with the symmetric key derived from the Plaintext into the version 2 IronCore document format
+     * used by ironoxide, ironnode and ironweb: a version byte and a length prefixed JSON header, followed by the
+     * AES-256-GCM encrypted data.
+     */
+    encryptDocument(plaintext: Plaintext, data: Buffer, header: DocumentHeader): Buffer;
+    /**
+     * Decrypt a document in the IronCore document format, including documents written by other IronCore SDKs. The header
+     * isn't authenticated, so check that its document id is the one you expected.
+     */
+    decryptDocument(plaintext: Plaintext, encryptedDocument: Buffer): DecryptedDocument;
+    /**
+     * Create a StreamEncryptor which encrypts with the symmetric key derived from the Plaintext. Data is encrypted in
+     * 64KB chunks, each with its own nonce, and the last chunk is marked so truncation is detected.
+     */
+    createStreamEncryptor(plaintext: Plaintext): StreamEncryptor;
+    createStreamDecryptor(plaintext: Plaintext): StreamDecryptor;
+    /**
+     * Encrypt a recrypt private key or Ed25519 private signing key under a passphrase into a versioned backup blob which
+     * records the Argon2id parameters. The key's public key is stored in the blob so it can be checked on import.
+     */
+    exportPrivateKey(
+        privateKey: PrivateKey | PrivateKeyHandle | PrivateSigningKey | SigningKeyPairHandle,
+        passphrase: string | Buffer,
+        params?: KdfParams
+    ): Buffer;
+    /**
+     * Decrypt a backup created by `exportPrivateKey`. Throws an error with code `InvalidCiphertext` if the passphrase is
+     * wrong or the blob was modified, and checks the decrypted key by recomputing its public key.
+     */
+    importPrivateKey(blob: Buffer, passphrase: string | Buffer, options?: ImportPrivateKeyOptions & {handle?: false}): ImportedPrivateKey<PrivateKey, PrivateSigningKey>;
+    importPrivateKey(
+        blob: Buffer,
+        passphrase: string | Buffer,
+        options: ImportPrivateKeyOptions & {handle: true}
+    ): ImportedPrivateKey<PrivateKeyHandle, SigningKeyPairHandle>;
+    importPrivateKeyAsync(
+        blob: Buffer,
+        passphrase: string | Buffer,
+        options?: ImportPrivateKeyOptions & {handle?: false}
+    ): Promise<ImportedPrivateKey<PrivateKey, PrivateSigningKey>>;
+    importPrivateKeyAsync(
+        blob: Buffer,
+        passphrase: string | Buffer,
+        options: ImportPrivateKeyOptions & {handle: true}
+    ): Promise<ImportedPrivateKey<PrivateKeyHandle, SigningKeyPairHandle>>;
+    schnorrSign(privateKey: PrivateKey | PrivateKeyHandle, publicKey: PublicKey | CompressedPublicKey, message: Buffer): Signature;
+    schnorrVerify(publicKey: PublicKey | CompressedPublicKey, augmentedPrivateKey: PrivateKey | PrivateKeyHandle | undefined, message: Buffer, signature: Signature): boolean;
+    /**
+     * Create the server's augmentation private key and the combined public key for a user. Only `userKeyPair.publicKey`
+     * is required; if `userKeyPair.privateKey` is provided it must match, or an error with code `PublicKeyMismatch` is
+     * thrown.
+     */
+    createAugmentedKeyPair(userKeyPair: {publicKey: PublicKey | CompressedPublicKey; privateKey?: PrivateKey | PrivateKeyHandle}, options?: GenerateKeyPairOptions & {handle?: false}): AugmentedKeyPair;
+    createAugmentedKeyPair(userKeyPair: {publicKey: PublicKey | CompressedPublicKey; privateKey?: PrivateKey | PrivateKeyHandle}, options: GenerateKeyPairOptions & {handle: true}): HandleAugmentedKeyPair;
+    /**
+     * Generate a transform key from the user's private key and augment it with the augmentation private key, so it
+     * transforms values encrypted to the combined public key.
+     */
+    generateAugmentedTransformKey(
+        userPrivateKey: PrivateKey | PrivateKeyHandle,
+        augmentationPrivateKey: PrivateKey | PrivateKeyHandle,
+        toPublicKey: PublicKey | CompressedPublicKey,
+        privateSigningKey: PrivateSigningKey | SigningKeyPairHandle
+    ): TransformKey;
+    /**
+     * Sign the message with the user's private key for the combined public key, proving possession of the user's half.
+     */
+    signForAugmentedKey(userPrivateKey: PrivateKey | PrivateKeyHandle, combinedPublicKey: PublicKey | CompressedPublicKey, message: Buffer): Signature;
+    /**
+     * Verify a signature from `signForAugmentedKey` using the server's augmentation private key.
+     */
+    verifyAugmentedKeySignature(
+        combinedPublicKey: PublicKey | CompressedPublicKey,
+        augmentationPrivateKey: PrivateKey | PrivateKeyHandle,
+        message: Buffer,
+        signature: Signature
+    ): boolean;
+}
+/**
+ * The 480-bit Recrypt API. Uses the same object shapes as Api256, but private keys and public key coordinates are 60
+ * bytes, plaintexts and encrypted temp keys are 720 bytes, hashed temp keys are 240 bytes and Schnorr signatures are 120
+ * bytes.
+ */
+export class Api480 {
     constructor();
     generateKeyPair(): KeyPair;
-    generateEd25519KeyPair(): SigningKeyPair;
-    ed25519Sign(privateKey: PrivateSigningKey, message: Buffer): Signature;
+    generateEd25519KeyPair(options?: GenerateEd25519KeyPairOptions & {handle?: false}): SigningKeyPair;
+    generateEd25519KeyPair(options: GenerateEd25519KeyPairOptions & {handle: true}): HandleSigningKeyPair;
+    ed25519Sign(privateKey: PrivateSigningKey | SigningKeyPairHandle, message: Buffer): Signature;
     ed25519Verify(publicKey: PublicSigningKey, message: Buffer, signature: Signature): boolean;
-    computeEd25519PublicKey(privateKey: PrivateSigningKey): PublicSigningKey;
+    computeEd25519PublicKey(privateKey: PrivateSigningKey | SigningKeyPairHandle): PublicSigningKey;
     generatePlaintext(): Plaintext;
-    generateTransformKey(fromPrivateKey: PrivateKey, toPublicKey: PublicKey, privateSigningKey: PrivateSigningKey): TransformKey;
+    generateTransformKey(fromPrivateKey: PrivateKey, toPublicKey: PublicKey | CompressedPublicKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): TransformKey;
     computePublicKey(privateKey: PrivateKey): PublicKey;
     deriveSymmetricKey(plaintext: Plaintext): Buffer;
-    encrypt(plaintext: Plaintext, toPublicKey: PublicKey, privateSigningKey: PrivateSigningKey): EncryptedValue;
-    transform(encryptedValue: EncryptedValue, transformKey: TransformKey, privateSigningKey: PrivateSigningKey): EncryptedValue;
+    encrypt(plaintext: Plaintext, toPublicKey: PublicKey | CompressedPublicKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): EncryptedValue;
+    transform(encryptedValue: EncryptedValue, transformKey: TransformKey, privateSigningKey: PrivateSigningKey | SigningKeyPairHandle): EncryptedValue;
     decrypt(encryptedValue: EncryptedValue, privateKey: PrivateKey): Plaintext;
-    schnorrSign(privateKey: Buffer, publicKey: PublicKey, message: Buffer): Signature;
-    schnorrVerify(publicKey: PublicKey, augmentedPrivateKey: Buffer | undefined, message: Buffer, signature: Signature): boolean;
+    schnorrSign(privateKey: Buffer, publicKey: PublicKey | CompressedPublicKey, message: Buffer): Signature;
+    schnorrVerify(publicKey: PublicKey | CompressedPublicKey, augmentedPrivateKey: Buffer | undefined, message: Buffer, signature: Signature): boolean;
 }
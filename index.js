@@ -6,24 +6,92 @@
  * This file is the bridge between the generated Rust binary and the JavaScript interface users call into.
  *
  * This file is not generated. Any changes made in the Rust code will need to be propagated out here, either by adding
- * bare functions to the export declaration at the bottom, or by adding new methods to the Api256 class.
+ * bare functions to the export declaration at the bottom, or by adding new methods to the Api256 or Api480 classes.
  *
  * The `index.d.ts` file is also manually maintained, add any new signatures to it.
  */
 
 const internal = require("./bin-package/index.node");
 
+class StreamEncryptor {
+    constructor(boxed) {
+        this.boxed = boxed;
+    }
+
+    update(chunk) {
+        return internal.streamEncryptorUpdate(this.boxed, chunk);
+    }
+
+    final() {
+        return internal.streamEncryptorFinal(this.boxed);
+    }
+}
+
+class StreamDecryptor {
+    constructor(boxed) {
+        this.boxed = boxed;
+    }
+
+    update(chunk) {
+        return internal.streamDecryptorUpdate(this.boxed, chunk);
+    }
+
+    final() {
+        return internal.streamDecryptorFinal(this.boxed);
+    }
+}
+
 class Api256 {
-    constructor() {
-        this.boxed = internal.createApi256();
+    constructor(options) {
+        this.boxed = internal.createApi256(options);
     }
 
-    generateKeyPair() {
-        return internal.generateKeyPair(this.boxed);
+    isDeterministic() {
+        return internal.isDeterministic(this.boxed);
+    }
+
+    generateKeyPair(options) {
+        return internal.generateKeyPair(this.boxed, options);
     }
 
-    generateEd25519KeyPair() {
-        return internal.generateEd25519KeyPair(this.boxed);
+    deriveKeyPairFromPassphrase(passphrase, salt, kdfParams, options) {
+        return internal.deriveKeyPairFromPassphrase(this.boxed, passphrase, salt, kdfParams, options);
+    }
+
+    deriveKeyPairFromPassphraseAsync(passphrase, salt, kdfParams, options) {
+        return internal.deriveKeyPairFromPassphraseAsync(this.boxed, passphrase, salt, kdfParams, options);
+    }
+
+    privateKeyToMnemonic(privateKey) {
+        return internal.privateKeyToMnemonic(privateKey);
+    }
+
+    mnemonicToPrivateKey(words, expectedPublicKey, options) {
+        return internal.mnemonicToPrivateKey(this.boxed, words, expectedPublicKey, options);
+    }
+
+    splitPrivateKey(privateKey, threshold, shares) {
+        return internal.splitPrivateKey(this.boxed, privateKey, threshold, shares);
+    }
+
+    combinePrivateKeyShares(shares, expectedPublicKey, options) {
+        return internal.combinePrivateKeyShares(this.boxed, shares, expectedPublicKey, options);
+    }
+
+    rotateUserPrivateKey(currentPrivateKey, options) {
+        return internal.rotateUserPrivateKey(this.boxed, currentPrivateKey, options);
+    }
+
+    rotateGroupKey(groupPrivateKey, memberPublicKeys, privateSigningKey, options) {
+        return internal.rotateGroupKey(this.boxed, groupPrivateKey, memberPublicKeys, privateSigningKey, options);
+    }
+
+    rotateGroupKeyAsync(groupPrivateKey, memberPublicKeys, privateSigningKey, options) {
+        return internal.rotateGroupKeyAsync(this.boxed, groupPrivateKey, memberPublicKeys, privateSigningKey, options);
+    }
+
+    generateEd25519KeyPair(options) {
+        return internal.generateEd25519KeyPair(this.boxed, options);
     }
 
     ed25519Sign(privateKey, message) {
@@ -34,6 +102,10 @@ class Api256 {
         return internal.ed25519Verify(publicKey, message, signature);
     }
 
+    verifyEncryptedValue(encryptedValue, expectedPublicSigningKey) {
+        return internal.verifyEncryptedValue(encryptedValue, expectedPublicSigningKey);
+    }
+
     computeEd25519PublicKey(privateKey) {
         return internal.computeEd25519PublicKey(privateKey);
     }
@@ -46,6 +118,10 @@ class Api256 {
         return internal.generateTransformKey(this.boxed, fromPrivateKey, toPublicKey, privateSigningKey);
     }
 
+    generateTransformKeyAsync(fromPrivateKey, toPublicKey, privateSigningKey) {
+        return internal.generateTransformKeyAsync(this.boxed, fromPrivateKey, toPublicKey, privateSigningKey);
+    }
+
     computePublicKey(privateKey) {
         return internal.computePublicKey(this.boxed, privateKey);
     }
@@ -54,18 +130,102 @@ class Api256 {
         return internal.deriveSymmetricKey(this.boxed, plaintext);
     }
 
+    derivePrivateKey(secretBytes, options) {
+        return internal.derivePrivateKey(this.boxed, secretBytes, options);
+    }
+
     encrypt(plaintext, toPublicKey, privateSigningKey) {
         return internal.encrypt(this.boxed, plaintext, toPublicKey, privateSigningKey);
     }
 
+    encryptMany(plaintext, toPublicKeys, privateSigningKey) {
+        return internal.encryptMany(this.boxed, plaintext, toPublicKeys, privateSigningKey);
+    }
+
+    encryptAsync(plaintext, toPublicKey, privateSigningKey) {
+        return internal.encryptAsync(this.boxed, plaintext, toPublicKey, privateSigningKey);
+    }
+
     transform(encryptedValue, transformKey, privateSigningKey) {
         return internal.transform(this.boxed, encryptedValue, transformKey, privateSigningKey);
     }
 
+    transformMany(encryptedValues, transformKey, privateSigningKey, options) {
+        return internal.transformMany(this.boxed, encryptedValues, transformKey, privateSigningKey, options);
+    }
+
+    transformAsync(encryptedValue, transformKey, privateSigningKey) {
+        return internal.transformAsync(this.boxed, encryptedValue, transformKey, privateSigningKey);
+    }
+
     decrypt(encryptedValue, privateKey) {
         return internal.decrypt(this.boxed, encryptedValue, privateKey);
     }
 
+    decryptAsync(encryptedValue, privateKey) {
+        return internal.decryptAsync(this.boxed, encryptedValue, privateKey);
+    }
+
+    decryptMany(encryptedValues, privateKey, options) {
+        return internal.decryptMany(this.boxed, encryptedValues, privateKey, options);
+    }
+
+    decryptManyAsync(encryptedValues, privateKey, options) {
+        return internal.decryptManyAsync(this.boxed, encryptedValues, privateKey, options);
+    }
+
+    encryptData(data, toPublicKey, privateSigningKey, aad) {
+        return internal.encryptData(this.boxed, data, toPublicKey, privateSigningKey, aad);
+    }
+
+    decryptData(envelope, privateKey, aad) {
+        return internal.decryptData(this.boxed, envelope, privateKey, aad);
+    }
+
+    createSharedDocument(data, recipients, privateSigningKey, aad) {
+        return internal.createSharedDocument(this.boxed, data, recipients, privateSigningKey, aad);
+    }
+
+    decryptSharedDocument(document, recipientId, privateKey, aad) {
+        return internal.decryptSharedDocument(this.boxed, document, recipientId, privateKey, aad);
+    }
+
+    grantAccess(document, recipientId, publicKey, authorPrivateKey, privateSigningKey) {
+        return internal.grantAccess(this.boxed, document, recipientId, publicKey, authorPrivateKey, privateSigningKey);
+    }
+
+    revokeAccess(document, recipientId) {
+        return internal.revokeAccess(document, recipientId);
+    }
+
+    encryptDocument(plaintext, data, header) {
+        return internal.encryptDocument(this.boxed, plaintext, data, header);
+    }
+
+    decryptDocument(plaintext, encryptedDocument) {
+        return internal.decryptDocument(this.boxed, plaintext, encryptedDocument);
+    }
+
+    createStreamEncryptor(plaintext) {
+        return new StreamEncryptor(internal.createStreamEncryptor(this.boxed, plaintext));
+    }
+
+    createStreamDecryptor(plaintext) {
+        return new StreamDecryptor(internal.createStreamDecryptor(this.boxed, plaintext));
+    }
+
+    exportPrivateKey(privateKey, passphrase, params) {
+        return internal.exportPrivateKey(this.boxed, privateKey, passphrase, params);
+    }
+
+    importPrivateKey(blob, passphrase, options) {
+        return internal.importPrivateKey(this.boxed, blob, passphrase, options);
+    }
+
+    importPrivateKeyAsync(blob, passphrase, options) {
+        return internal.importPrivateKeyAsync(this.boxed, blob, passphrase, options);
+    }
+
     schnorrSign(privateKey, publicKey, message) {
         return internal.schnorrSign(this.boxed, privateKey, publicKey, message);
     }
@@ -73,13 +233,111 @@ class Api256 {
     schnorrVerify(publicKey, augmentedPrivateKey, message, signature) {
         return internal.schnorrVerify(this.boxed, publicKey, augmentedPrivateKey, message, signature);
     }
+
+    createAugmentedKeyPair(userKeyPair, options) {
+        return internal.createAugmentedKeyPair(this.boxed, userKeyPair, options);
+    }
+
+    generateAugmentedTransformKey(userPrivateKey, augmentationPrivateKey, toPublicKey, privateSigningKey) {
+        return internal.generateAugmentedTransformKey(this.boxed, userPrivateKey, augmentationPrivateKey, toPublicKey, privateSigningKey);
+    }
+
+    signForAugmentedKey(userPrivateKey, combinedPublicKey, message) {
+        return internal.signForAugmentedKey(this.boxed, userPrivateKey, combinedPublicKey, message);
+    }
+
+    verifyAugmentedKeySignature(combinedPublicKey, augmentationPrivateKey, message, signature) {
+        return internal.verifyAugmentedKeySignature(this.boxed, combinedPublicKey, augmentationPrivateKey, message, signature);
+    }
+}
+
+class Api480 {
+    constructor() {
+        this.boxed = internal.createApi480();
+    }
+
+    generateKeyPair() {
+        return internal.generateKeyPair480(this.boxed);
+    }
+
+    generateEd25519KeyPair(options) {
+        return internal.generateEd25519KeyPair480(this.boxed, options);
+    }
+
+    ed25519Sign(privateKey, message) {
+        return internal.ed25519Sign(privateKey, message);
+    }
+
+    ed25519Verify(publicKey, message, signature) {
+        return internal.ed25519Verify(publicKey, message, signature);
+    }
+
+    computeEd25519PublicKey(privateKey) {
+        return internal.computeEd25519PublicKey(privateKey);
+    }
+
+    generatePlaintext() {
+        return internal.generatePlaintext480(this.boxed);
+    }
+
+    generateTransformKey(fromPrivateKey, toPublicKey, privateSigningKey) {
+        return internal.generateTransformKey480(this.boxed, fromPrivateKey, toPublicKey, privateSigningKey);
+    }
+
+    computePublicKey(privateKey) {
+        return internal.computePublicKey480(this.boxed, privateKey);
+    }
+
+    deriveSymmetricKey(plaintext) {
+        return internal.deriveSymmetricKey480(this.boxed, plaintext);
+    }
+
+    encrypt(plaintext, toPublicKey, privateSigningKey) {
+        return internal.encrypt480(this.boxed, plaintext, toPublicKey, privateSigningKey);
+    }
+
+    transform(encryptedValue, transformKey, privateSigningKey) {
+        return internal.transform480(this.boxed, encryptedValue, transformKey, privateSigningKey);
+    }
+
+    decrypt(encryptedValue, privateKey) {
+        return internal.decrypt480(this.boxed, encryptedValue, privateKey);
+    }
+
+    schnorrSign(privateKey, publicKey, message) {
+        return internal.schnorrSign480(this.boxed, privateKey, publicKey, message);
+    }
+
+    schnorrVerify(publicKey, augmentedPrivateKey, message, signature) {
+        return internal.schnorrVerify480(this.boxed, publicKey, augmentedPrivateKey, message, signature);
+    }
 }
 
 module.exports = {
     Api256,
+    Api480,
     augmentPublicKey256: internal.augmentPublicKey256,
     augmentTransformKey256: internal.augmentTransformKey256,
+    applyRotationToTransformKeys: internal.applyRotationToTransformKeys,
     transformKeyToBytes256: internal.transformKeyToBytes256,
+    compressPublicKey: internal.compressPublicKey,
+    decompressPublicKey: internal.decompressPublicKey,
+    encryptedValueToBytes: internal.encryptedValueToBytes,
+    encryptedValueFromBytes: internal.encryptedValueFromBytes,
+    transformKeyToBytes: internal.transformKeyToBytes,
+    transformKeyFromBytes: internal.transformKeyFromBytes,
+    parseDocumentHeader: internal.parseDocumentHeader,
+    privateKeyHandleToBytes: internal.privateKeyHandleToBytes,
+    privateKeyHandleFromBytes: internal.privateKeyHandleFromBytes,
+    signingKeyPairHandleToBytes: internal.signingKeyPairHandleToBytes,
+    signingKeyPairHandleFromBytes: internal.signingKeyPairHandleFromBytes,
     addPrivateKeys: internal.addPrivateKeys,
     subtractPrivateKeys: internal.subtractPrivateKeys,
+    augmentPublicKey480: internal.augmentPublicKey480,
+    augmentTransformKey480: internal.augmentTransformKey480,
+    transformKeyToBytes480: internal.transformKeyToBytes480,
+    compressPublicKey480: internal.compressPublicKey480,
+    decompressPublicKey480: internal.decompressPublicKey480,
+    addPrivateKeys480: internal.addPrivateKeys480,
+    subtractPrivateKeys480: internal.subtractPrivateKeys480,
 };
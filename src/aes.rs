@@ -0,0 +1,110 @@
+use crate::util::{self, ErrorCode, ErrorKind};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use neon::prelude::*;
+use rand::RngCore;
+use rand::rngs::OsRng;
+
+// AES-256-GCM encryption of data under the symmetric key derived from a recrypt Plaintext. The key is only ever held in
+// Rust. Encrypted data is laid out as the 12 byte nonce, followed by the ciphertext and the 16 byte GCM tag.
+
+pub const NONCE_LEN: usize = 12;
+pub const TAG_LEN: usize = 16;
+
+#[derive(Debug)]
+pub enum AesErr {
+    /// The encrypted data was too short to hold a nonce and tag.
+    TooShort(usize),
+    /// The GCM tag didn't verify, so the data was modified or the wrong key or AAD was used.
+    AuthenticationFailed,
+}
+
+/// Unwrap the result of an AES operation. On failure throws a JS error naming the field which held the encrypted data.
+pub fn aes_result_or_throw<'a, T: Context<'a>, R>(
+    cx: &mut T,
+    result: Result<R, AesErr>,
+    field_path: &str,
+) -> NeonResult<R> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(AesErr::TooShort(len)) => util::throw_error(
+            cx,
+            ErrorKind::RangeError,
+            ErrorCode::InvalidBufferLength,
+            Some(field_path),
+            &format!(
+                "Provided Buffer for '{}' is too short to be encrypted data. Expected at least {} bytes but got {}.",
+                field_path,
+                NONCE_LEN + TAG_LEN,
+                len
+            ),
+        ),
+        Err(AesErr::AuthenticationFailed) => util::throw_error(
+            cx,
+            ErrorKind::Error,
+            ErrorCode::InvalidCiphertext,
+            Some(field_path),
+            &format!(
+                "Provided value for '{}' failed to decrypt. It was modified or was encrypted with a different key or AAD.",
+                field_path
+            ),
+        ),
+    }
+}
+
+/// Create the AES-256-GCM cipher for the provided 32 byte key, such as the bytes of a DerivedSymmetricKey.
+pub fn cipher(key: &[u8; 32]) -> Aes256Gcm {
+    Aes256Gcm::new(key.into())
+}
+
+/// Encrypt the provided data with the provided nonce, returning the ciphertext followed by the tag. Callers must never
+/// reuse a nonce with the same key.
+pub fn encrypt_with_nonce(
+    cipher: &Aes256Gcm,
+    nonce: [u8; NONCE_LEN],
+    data: &[u8],
+    aad: &[u8],
+) -> Vec<u8> {
+    cipher
+        .encrypt(&Nonce::from(nonce), Payload { msg: data, aad })
+        .expect("AES-GCM encryption only fails for inputs larger than 64GB")
+}
+
+/// Decrypt ciphertext created by `encrypt_with_nonce`. Fails if the tag doesn't verify with the provided nonce and AAD.
+pub fn decrypt_with_nonce(
+    cipher: &Aes256Gcm,
+    nonce: [u8; NONCE_LEN],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, AesErr> {
+    cipher
+        .decrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| AesErr::AuthenticationFailed)
+}
+
+/// Encrypt the provided data with a fresh random nonce. The AAD is authenticated but not included in the output.
+pub fn encrypt(key: &[u8; 32], data: &[u8], aad: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = encrypt_with_nonce(&cipher(key), nonce, data, aad);
+    let mut encrypted = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&ciphertext);
+    encrypted
+}
+
+/// Decrypt data created by `encrypt`. Fails if the tag doesn't verify with the provided key and AAD.
+pub fn decrypt(key: &[u8; 32], encrypted: &[u8], aad: &[u8]) -> Result<Vec<u8>, AesErr> {
+    if encrypted.len() < NONCE_LEN + TAG_LEN {
+        return Err(AesErr::TooShort(encrypted.len()));
+    }
+    let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("split at NONCE_LEN");
+    decrypt_with_nonce(&cipher(key), nonce, ciphertext, aad)
+}
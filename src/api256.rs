@@ -1,16 +1,79 @@
+use crate::aes;
+use crate::compression;
+use crate::document;
+use crate::handles;
+use crate::mnemonic;
+use crate::passphrase;
+use crate::serialization;
+use crate::shamir;
+use crate::stream::{self, StreamDecryptorHandle, StreamEncryptorHandle};
 use crate::util;
-use neon::{prelude::*, types::JsBuffer};
+use neon::{prelude::*, types::JsBuffer, types::buffer::TypedArray};
+use rand::rngs::{OsRng, adapter::ReseedingRng};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::{ChaCha20Core, ChaCha20Rng};
+use rayon::prelude::*;
 use recrypt::api::{
-    CryptoOps, DefaultRng, Ed25519, Ed25519Ops, Hashable, KeyGenOps, PublicSigningKey, RandomBytes,
-    Recrypt, SchnorrOps, Sha256, SigningKeypair,
+    CryptoOps, DefaultRng, DerivedSymmetricKey, Ed25519, Ed25519Ops, EncryptedValue, Hashable,
+    KeyGenOps, Plaintext, PrivateKey, PublicKey, PublicSigningKey, RandomBytes, Recrypt,
+    RecryptErr, SchnorrOps, Sha256, SigningKeypair,
 };
+use std::cell::RefCell;
+use std::sync::Arc;
+use zeroize::Zeroizing;
 
 pub struct RecryptApi256 {
-    api: Recrypt<Sha256, Ed25519, RandomBytes<DefaultRng>>,
+    // Shared so that the `*_async` functions can hand a reference to the libuv threadpool.
+    api: Arc<Recrypt<Sha256, Ed25519, RandomBytes<Api256Rng>>>,
+    // Set when the instance was created from a seed, so production code can refuse to use it.
+    deterministic: bool,
 }
 
 impl Finalize for RecryptApi256 {}
 
+/// The CSPRNG backing a RecryptApi256. Everything uses the reseeding OS backed default unless a seed is passed to
+/// `createApi256`, in which case every value the instance generates is reproducible. The seeded variant is only meant
+/// for tests and fixtures.
+pub enum Api256Rng {
+    Default(DefaultRng),
+    Seeded(ChaCha20Rng),
+}
+
+impl RngCore for Api256Rng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Api256Rng::Default(rng) => rng.next_u32(),
+            Api256Rng::Seeded(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Api256Rng::Default(rng) => rng.next_u64(),
+            Api256Rng::Seeded(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Api256Rng::Default(rng) => rng.fill_bytes(dest),
+            Api256Rng::Seeded(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            Api256Rng::Default(rng) => rng.try_fill_bytes(dest),
+            Api256Rng::Seeded(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+impl CryptoRng for Api256Rng {}
+
+/// How many bytes the default RNG generates before reseeding itself from the OS. Matches `Recrypt::new()`.
+const BYTES_BEFORE_RESEEDING: u64 = 1024 * 1024;
+
 // All the functions in here take `FunctionContext`, which contains all the actual JS arguments.
 //
 // If something takes `cx.argument<JsBox<RecryptApi256>>(0)` that means it manually needs to be passed the
@@ -18,51 +81,404 @@ impl Finalize for RecryptApi256 {}
 //
 // Otherwise, each index in the `cx.argument` is an expected parameter, so make sure the mapping function takes that
 // into account.
+//
+// `createApi256` takes an optional options object. If `options.seed` is set to a 32 byte Buffer the instance uses a
+// ChaCha20 CSPRNG seeded from it, so it will generate the same keys and encrypted values every time. This is only for
+// reproducible tests and fixtures.
 pub fn api256_create_recrypt_api_256(mut cx: FunctionContext) -> JsResult<JsBox<RecryptApi256>> {
+    let options: Option<Handle<JsValue>> = cx.argument_opt(0);
+
+    let rng = match util::get_option::<JsBuffer, _>(&mut cx, options, "seed", "Buffer")? {
+        Some(seed_buffer) => Api256Rng::Seeded(ChaCha20Rng::from_seed(
+            util::buffer_to_fixed_32_bytes(&mut cx, seed_buffer, "options.seed")?,
+        )),
+        None => Api256Rng::Default(ReseedingRng::new(
+            ChaCha20Core::from_entropy(),
+            BYTES_BEFORE_RESEEDING,
+            OsRng,
+        )),
+    };
+    let deterministic = matches!(rng, Api256Rng::Seeded(_));
+
     Ok(cx.boxed(RecryptApi256 {
-        api: Recrypt::new(),
+        api: Arc::new(Recrypt::new_with_rand(rng)),
+        deterministic,
     }))
 }
 
+/// Returns true if the provided instance was created with a seed, meaning everything it generates is reproducible.
+pub fn api256_is_deterministic(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+
+    Ok(cx.boolean(recrypt_api_256.deterministic))
+}
+
+/// Generate a new key pair. If the `handle` option is set, the private key is returned as a PrivateKeyHandle which keeps
+/// it in native memory instead of a Buffer.
 pub fn api256_generate_key_pair(mut cx: FunctionContext) -> JsResult<JsObject> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(1);
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
 
-    let (priv_key, pub_key) = recrypt_api_256.api.generate_key_pair().unwrap();
-    let key_pair = cx.empty_object();
-    let priv_key_buffer = util::bytes_to_buffer(&mut cx, priv_key.bytes())?;
-    let public_key_obj = util::public_key_to_js_object(&mut cx, &pub_key)?;
+    let (priv_key, pub_key) =
+        util::recrypt_result_or_throw(&mut cx, recrypt_api_256.api.generate_key_pair())?;
+    key_pair_to_js_object(&mut cx, priv_key, &pub_key, as_handle)
+}
 
-    key_pair.set(&mut cx, "privateKey", priv_key_buffer)?;
-    key_pair.set(&mut cx, "publicKey", public_key_obj)?;
+/// Deterministically derive a key pair from a passphrase. The passphrase is stretched with Argon2id using the provided
+/// salt and KDF parameters into the private key, so the same inputs always give the same key pair. Returns the same
+/// `{privateKey, publicKey}` object as `api256_generate_key_pair`, including the `handle` option.
+pub fn api256_derive_key_pair_from_passphrase(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let args = passphrase_key_pair_args(&mut cx)?;
+
+    let (priv_key, pub_key) = util::recrypt_result_or_throw(
+        &mut cx,
+        derive_key_pair_from_passphrase(&args.api, &args.passphrase, &args.salt, args.params),
+    )?;
+    key_pair_to_js_object(&mut cx, priv_key, &pub_key, args.as_handle)
+}
 
-    Ok(key_pair)
+/// Same as `api256_derive_key_pair_from_passphrase`, but Argon2id is run on the libuv threadpool. Returns a Promise which
+/// resolves with the key pair object.
+pub fn api256_derive_key_pair_from_passphrase_async(
+    mut cx: FunctionContext,
+) -> JsResult<JsPromise> {
+    let args = match util::parse_or_reject(&mut cx, passphrase_key_pair_args) {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+    let as_handle = args.as_handle;
+
+    let promise = cx
+        .task(move || {
+            derive_key_pair_from_passphrase(&args.api, &args.passphrase, &args.salt, args.params)
+        })
+        .promise(move |mut cx, result| {
+            let (priv_key, pub_key) = util::recrypt_result_or_throw(&mut cx, result)?;
+            key_pair_to_js_object(&mut cx, priv_key, &pub_key, as_handle)
+        });
+
+    Ok(promise)
 }
 
-pub fn api256_generate_ed25519_key_pair(mut cx: FunctionContext) -> JsResult<JsObject> {
+/// Decode a 24 word mnemonic created by `private_key_to_mnemonic` back into a key pair. Throws if a word is unknown, the
+/// checksum doesn't match or the decoded bytes aren't a valid private key. If an expected public key is provided the
+/// public key computed from the decoded private key must match it. Returns the same `{privateKey, publicKey}` object as
+/// `api256_generate_key_pair`, including the `handle` option.
+pub fn api256_mnemonic_to_private_key(mut cx: FunctionContext) -> JsResult<JsObject> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let words_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let expected_public_key_arg: Option<Handle<JsValue>> = cx.argument_opt(2);
+    let options: Option<Handle<JsValue>> = cx.argument_opt(3);
+
+    let words = mnemonic::js_value_to_mnemonic_words(&mut cx, words_value, "words")?;
+    let expected_public_key = optional_expected_public_key(&mut cx, expected_public_key_arg)?;
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+
+    let private_key_bytes = serialization::serialization_result_or_throw(
+        &mut cx,
+        mnemonic::mnemonic_to_bytes(&words, "words"),
+    )?;
+    let priv_key = PrivateKey::new(private_key_bytes);
+    if priv_key.bytes() != &private_key_bytes {
+        return util::throw_error(
+            &mut cx,
+            util::ErrorKind::Error,
+            util::ErrorCode::InvalidEncoding,
+            Some("words"),
+            "Provided mnemonic for 'words' doesn't encode a private key inside the field.",
+        );
+    }
+    let pub_key =
+        util::recrypt_result_or_throw(&mut cx, recrypt_api_256.api.compute_public_key(&priv_key))?;
+    if let Some(expected_public_key) = expected_public_key {
+        if pub_key != expected_public_key {
+            return util::throw_error(
+                &mut cx,
+                util::ErrorKind::Error,
+                util::ErrorCode::PublicKeyMismatch,
+                Some("expectedPublicKey"),
+                "The private key decoded from 'words' doesn't match 'expectedPublicKey'.",
+            );
+        }
+    }
+    key_pair_to_js_object(&mut cx, priv_key, &pub_key, as_handle)
+}
 
-    let signing_key_pair = recrypt_api_256.api.generate_ed25519_key_pair();
+/// Split a private key into `shares` serialized shares, any `threshold` of which can be combined to recover it. Each share
+/// records the public key of the private key so combining can check the result.
+pub fn api256_split_private_key(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let threshold_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let shares_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
+    let threshold = shamir::js_value_to_share_count(&mut cx, threshold_value, "threshold")?;
+    let share_count = shamir::js_value_to_share_count(&mut cx, shares_value, "shares")?;
+    if threshold > share_count {
+        return util::throw_error(
+            &mut cx,
+            util::ErrorKind::RangeError,
+            util::ErrorCode::InputWrongSize,
+            Some("threshold"),
+            &format!(
+                "Provided value for 'threshold' can't be more than 'shares'. Got a threshold of {} for {} shares.",
+                threshold, share_count
+            ),
+        );
+    }
+    let public_key = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256.api.compute_public_key(&private_key),
+    )?;
+    let (x, y) = public_key.bytes_x_y();
+    let mut public_key_bytes = [0u8; 64];
+    public_key_bytes[..32].copy_from_slice(x);
+    public_key_bytes[32..].copy_from_slice(y);
+
+    let shares = shamir::split_secret(
+        private_key.bytes(),
+        &public_key_bytes,
+        threshold,
+        share_count,
+    );
+    let shares_array = cx.empty_array();
+    for (i, share) in shares.iter().enumerate() {
+        let share_buffer = util::bytes_to_buffer(&mut cx, share)?;
+        shares_array.set(&mut cx, i as u32, share_buffer)?;
+    }
+    Ok(shares_array)
+}
+
+/// Combine shares created by `api256_split_private_key` back into the private key. Every share's checksum is verified and
+/// the recovered private key must produce the public key recorded in the shares. If an expected public key is provided
+/// it must match as well. Returns the same `{privateKey, publicKey}` object as `api256_generate_key_pair`, including the
+/// `handle` option.
+pub fn api256_combine_private_key_shares(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let shares_array: Handle<JsArray> = cx.argument::<JsArray>(1)?;
+    let expected_public_key_arg: Option<Handle<JsValue>> = cx.argument_opt(2);
+    let options: Option<Handle<JsValue>> = cx.argument_opt(3);
+
+    let share_values = shares_array.to_vec(&mut cx)?;
+    if share_values.is_empty() {
+        return util::throw_error(
+            &mut cx,
+            util::ErrorKind::RangeError,
+            util::ErrorCode::EmptyArray,
+            Some("shares"),
+            "Provided value for 'shares' must contain at least one share.",
+        );
+    }
+    let mut shares = Vec::with_capacity(share_values.len());
+    for (i, share_value) in share_values.into_iter().enumerate() {
+        let share_path = format!("shares[{}]", i);
+        let share_buffer: Handle<JsBuffer> =
+            util::downcast_field(&mut cx, share_value, &share_path, "Buffer")?;
+        let share_bytes = util::buffer_to_variable_bytes(&cx, share_buffer);
+        shares.push(serialization::serialization_result_or_throw(
+            &mut cx,
+            shamir::parse_share(&share_bytes, &share_path),
+        )?);
+    }
+    let expected_public_key = optional_expected_public_key(&mut cx, expected_public_key_arg)?;
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+
+    let (private_key_bytes, share_public_key) = serialization::serialization_result_or_throw(
+        &mut cx,
+        shamir::combine_shares(&shares, "shares"),
+    )?;
+    let priv_key = PrivateKey::new(private_key_bytes);
+    let pub_key =
+        util::recrypt_result_or_throw(&mut cx, recrypt_api_256.api.compute_public_key(&priv_key))?;
+    let (x, y) = pub_key.bytes_x_y();
+    if x[..] != share_public_key[..32] || y[..] != share_public_key[32..] {
+        return util::throw_error(
+            &mut cx,
+            util::ErrorKind::Error,
+            util::ErrorCode::PublicKeyMismatch,
+            Some("shares"),
+            "The private key combined from 'shares' doesn't match the public key recorded in them.",
+        );
+    }
+    if let Some(expected_public_key) = expected_public_key {
+        if pub_key != expected_public_key {
+            return util::throw_error(
+                &mut cx,
+                util::ErrorKind::Error,
+                util::ErrorCode::PublicKeyMismatch,
+                Some("expectedPublicKey"),
+                "The private key combined from 'shares' doesn't match 'expectedPublicKey'.",
+            );
+        }
+    }
+    key_pair_to_js_object(&mut cx, priv_key, &pub_key, as_handle)
+}
 
-    let signing_key_pair_obj: Handle<JsObject> = cx.empty_object();
-    let priv_key_buffer = util::bytes_to_buffer(&mut cx, signing_key_pair.bytes())?;
-    let pub_key_buffer = util::bytes_to_buffer(&mut cx, signing_key_pair.public_key().bytes())?;
+/// Rotate a user's private key by adding a random augmentation factor to it. Returns an object with the new
+/// `privateKey`, the `augmentationFactor` and the new `publicKey`, which is checked to be the current public key augmented
+/// with the factor's public key. The factor should be passed to `apply_rotation_to_transform_keys` to update the user's
+/// outgoing transform keys. If the `handle` option is set both private keys are returned as PrivateKeyHandles.
+pub fn api256_rotate_user_private_key(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let current_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(2);
+
+    let current_private_key =
+        handles::js_value_to_private_key(&mut cx, current_private_key_value, "currentPrivateKey")?;
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+
+    let rotation = rotate_private_key(
+        &mut cx,
+        &recrypt_api_256.api,
+        &current_private_key,
+        "currentPrivateKey",
+    )?;
+    rotation_to_js_object(&mut cx, rotation, as_handle)
+}
 
-    signing_key_pair_obj.set(&mut cx, "privateKey", priv_key_buffer)?;
-    signing_key_pair_obj.set(&mut cx, "publicKey", pub_key_buffer)?;
+/// Rotate a group's private key the same way as `api256_rotate_user_private_key` and generate a fresh transform key from
+/// the new group private key to each of the members' public keys. Takes an optional options object: if `parallel` is set
+/// the transform keys are generated across all CPU cores, and if `handle` is set the private keys are returned as
+/// PrivateKeyHandles. Returns the rotation object with a `transformKeys` array, which has one entry per member, in
+/// order, that is either the TransformKey object or the error that occurred for that member.
+pub fn api256_rotate_group_key(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let group_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let member_public_keys_array: Handle<JsArray> = cx.argument::<JsArray>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(4);
+
+    let group_private_key =
+        handles::js_value_to_private_key(&mut cx, group_private_key_value, "groupPrivateKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+    let parallel = util::get_bool_option(&mut cx, options, "parallel")?;
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+    let member_public_keys = util::js_array_to_batch(
+        &mut cx,
+        member_public_keys_array,
+        "memberPublicKeys",
+        util::js_object_to_public_key,
+    )?;
+
+    let api = &recrypt_api_256.api;
+    let rotation = rotate_private_key(&mut cx, api, &group_private_key, "groupPrivateKey")?;
+    let new_private_key = &rotation.0;
+    let results = run_batch(member_public_keys, parallel, |member_public_key| {
+        api.generate_transform_key(new_private_key, &member_public_key, &signing_key_pair)
+    });
+
+    let transform_keys = util::batch_results_to_js_array(&mut cx, results, |cx, transform_key| {
+        Ok(util::transform_key_to_js_object(cx, transform_key)?.upcast())
+    })?;
+    let rotation_obj = rotation_to_js_object(&mut cx, rotation, as_handle)?;
+    rotation_obj.set(&mut cx, "transformKeys", transform_keys)?;
+    Ok(rotation_obj)
+}
 
-    Ok(signing_key_pair_obj)
+/// Same as `api256_rotate_group_key`, but the transform keys are generated on the libuv threadpool. Returns a Promise
+/// which resolves with the rotation object.
+pub fn api256_rotate_group_key_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let args = util::parse_or_reject(&mut cx, |cx| {
+        let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+        let group_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+        let member_public_keys_array: Handle<JsArray> = cx.argument::<JsArray>(2)?;
+        let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+        let options: Option<Handle<JsValue>> = cx.argument_opt(4);
+
+        let group_private_key =
+            handles::js_value_to_private_key(cx, group_private_key_value, "groupPrivateKey")?;
+        let signing_key_pair = handles::js_value_to_signing_key_pair(
+            cx,
+            private_signing_key_value,
+            "privateSigningKey",
+        )?;
+        let parallel = util::get_bool_option(cx, options, "parallel")?;
+        let as_handle = util::get_bool_option(cx, options, "handle")?;
+        let member_public_keys = util::js_array_to_batch(
+            cx,
+            member_public_keys_array,
+            "memberPublicKeys",
+            util::js_object_to_public_key,
+        )?;
+
+        // Conversion errors have to be rooted so they can be carried over to the completion callback.
+        let (inputs, slots) = split_batch(member_public_keys);
+        let mut rooted_slots = Vec::with_capacity(slots.len());
+        for slot in slots {
+            rooted_slots.push(match slot {
+                Ok(()) => Ok(()),
+                Err(error) => Err(error.downcast_or_throw::<JsObject, _>(cx)?.root(cx)),
+            });
+        }
+        let api = recrypt_api_256.api.clone();
+        let rotation = rotate_private_key(cx, &api, &group_private_key, "groupPrivateKey")?;
+        Ok((
+            api,
+            rotation,
+            signing_key_pair,
+            parallel,
+            as_handle,
+            inputs,
+            rooted_slots,
+        ))
+    });
+    let (api, rotation, signing_key_pair, parallel, as_handle, inputs, rooted_slots) = match args {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+    let new_private_key = rotation.0.clone();
+
+    let promise = cx
+        .task(move || {
+            map_inputs(inputs, parallel, |member_public_key| {
+                api.generate_transform_key(&new_private_key, &member_public_key, &signing_key_pair)
+            })
+        })
+        .promise(move |mut cx, outputs| {
+            let slots = rooted_slots
+                .into_iter()
+                .map(|slot| slot.map_err(|root| root.into_inner(&mut cx).upcast()))
+                .collect();
+            let results = merge_batch(slots, outputs);
+            let transform_keys =
+                util::batch_results_to_js_array(&mut cx, results, |cx, transform_key| {
+                    Ok(util::transform_key_to_js_object(cx, transform_key)?.upcast())
+                })?;
+            let rotation_obj = rotation_to_js_object(&mut cx, rotation, as_handle)?;
+            rotation_obj.set(&mut cx, "transformKeys", transform_keys)?;
+            Ok(rotation_obj)
+        });
+
+    Ok(promise)
+}
+
+/// Generate a new Ed25519 signing key pair. If the `handle` option is set, the private key is returned as a
+/// SigningKeyPairHandle which keeps it in native memory instead of a Buffer.
+pub fn api256_generate_ed25519_key_pair(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(1);
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+
+    let signing_key_pair = recrypt_api_256.api.generate_ed25519_key_pair();
+
+    handles::signing_key_pair_to_js_object(&mut cx, signing_key_pair, as_handle)
 }
 
 pub fn api256_ed25519_sign(mut cx: FunctionContext) -> JsResult<JsBuffer> {
-    let private_signing_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(0)?;
     let message_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
 
-    let signing_key_pair = SigningKeypair::from_bytes(&util::buffer_to_fixed_64_bytes(
-        &cx,
-        private_signing_key_buffer,
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
         "privateSigningKey",
-    ))
-    .unwrap();
+    )?;
 
     let signature = signing_key_pair.sign(&util::buffer_to_variable_bytes(&cx, message_buffer));
 
@@ -75,28 +491,95 @@ pub fn api256_ed25519_verify(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     let signature_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(2)?;
 
     let public_signing_key = PublicSigningKey::new(util::buffer_to_fixed_32_bytes(
-        &cx,
+        &mut cx,
         public_signing_key_buffer,
         "publicSigningKey",
-    ));
+    )?);
+    let signature = util::buffer_to_ed25519_signature(&mut cx, signature_buffer, "signature")?;
 
     let verified = public_signing_key.verify(
         &util::buffer_to_variable_bytes(&cx, message_buffer),
-        &util::buffer_to_ed25519_signature(&cx, signature_buffer),
+        &signature,
     );
 
     Ok(cx.boolean(verified))
 }
 
+/// Check an EncryptedValue's Ed25519 signature without decrypting it, so values which have been tampered with can be
+/// rejected by parties which don't hold any private keys. If `expectedPublicSigningKey` is provided the value must also
+/// have been signed with it. Returns `{valid: true}` or `{valid: false, code, field}`, where `code` and `field` describe
+/// the first check which failed in the same way as a thrown error would.
+pub fn api256_verify_encrypted_value(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let expected_public_signing_key_buffer: Option<Handle<JsValue>> = cx.argument_opt(1);
+
+    let encrypted_value =
+        util::js_object_to_encrypted_value(&mut cx, encrypted_value_obj, "encryptedValue")?;
+    let expected_public_signing_key = match util::downcast_optional_argument::<JsBuffer, _>(
+        &mut cx,
+        expected_public_signing_key_buffer,
+        "expectedPublicSigningKey",
+        "Buffer",
+    )? {
+        Some(buffer) => Some(PublicSigningKey::new(util::buffer_to_fixed_32_bytes(
+            &mut cx,
+            buffer,
+            "expectedPublicSigningKey",
+        )?)),
+        None => None,
+    };
+
+    let (public_signing_key, signature) = match &encrypted_value {
+        EncryptedValue::EncryptedOnceValue {
+            public_signing_key,
+            signature,
+            ..
+        }
+        | EncryptedValue::TransformedValue {
+            public_signing_key,
+            signature,
+            ..
+        } => (*public_signing_key, *signature),
+    };
+
+    let failure =
+        if expected_public_signing_key.is_some_and(|expected| expected != public_signing_key) {
+            Some((
+                util::ErrorCode::PublicSigningKeyMismatch,
+                "encryptedValue.publicSigningKey",
+            ))
+        } else if !public_signing_key.verify(
+            &serialization::encrypted_value_signed_bytes(&encrypted_value),
+            &signature,
+        ) {
+            Some((
+                util::ErrorCode::InvalidEncryptedMessageSignature,
+                "encryptedValue.signature",
+            ))
+        } else {
+            None
+        };
+
+    let result = cx.empty_object();
+    let valid = cx.boolean(failure.is_none());
+    result.set(&mut cx, "valid", valid)?;
+    if let Some((code, field)) = failure {
+        let code = cx.string(code.as_str());
+        let field = cx.string(field);
+        result.set(&mut cx, "code", code)?;
+        result.set(&mut cx, "field", field)?;
+    }
+    Ok(result)
+}
+
 pub fn api256_compute_ed25519_public_key(mut cx: FunctionContext) -> JsResult<JsBuffer> {
-    let private_signing_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(0)?;
 
-    let signing_key_pair = SigningKeypair::from_bytes(&util::buffer_to_fixed_64_bytes(
-        &cx,
-        private_signing_key_buffer,
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
         "privateSigningKey",
-    ))
-    .unwrap();
+    )?;
 
     let public_signing_key = signing_key_pair.public_key();
     util::bytes_to_buffer(&mut cx, public_signing_key.bytes())
@@ -112,38 +595,83 @@ pub fn api256_generate_plaintext(mut cx: FunctionContext) -> JsResult<JsBuffer>
 
 pub fn api256_generate_transform_key(mut cx: FunctionContext) -> JsResult<JsObject> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
-    let from_private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let from_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
     let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
-    let private_signing_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
 
-    let to_public_key = util::js_object_to_public_key(&mut cx, to_public_key_obj);
-    let signing_key_pair = SigningKeypair::from_bytes(&util::buffer_to_fixed_64_bytes(
-        &cx,
-        private_signing_key_buffer,
+    let to_public_key = util::js_object_to_public_key(&mut cx, to_public_key_obj, "toPublicKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
         "privateSigningKey",
-    ))
-    .unwrap();
+    )?;
 
-    let transform_key = recrypt_api_256
-        .api
-        .generate_transform_key(
-            &util::buffer_to_private_key(&cx, from_private_key_buffer),
+    let from_private_key =
+        handles::js_value_to_private_key(&mut cx, from_private_key_value, "fromPrivateKey")?;
+
+    let transform_key = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256.api.generate_transform_key(
+            &from_private_key,
             &to_public_key,
             &signing_key_pair,
-        )
-        .unwrap();
+        ),
+    )?;
 
     util::transform_key_to_js_object(&mut cx, transform_key)
 }
 
+/// Same as `api256_generate_transform_key`, but the transform key is generated on the libuv threadpool. Returns a Promise
+/// which resolves with the TransformKey object.
+pub fn api256_generate_transform_key_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let args = util::parse_or_reject(&mut cx, |cx| {
+        let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+        let from_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+        let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+        let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+        let to_public_key = util::js_object_to_public_key(cx, to_public_key_obj, "toPublicKey")?;
+        let signing_key_pair = handles::js_value_to_signing_key_pair(
+            cx,
+            private_signing_key_value,
+            "privateSigningKey",
+        )?;
+        let from_private_key =
+            handles::js_value_to_private_key(cx, from_private_key_value, "fromPrivateKey")?;
+        Ok((
+            recrypt_api_256.api.clone(),
+            from_private_key,
+            to_public_key,
+            signing_key_pair,
+        ))
+    });
+    let (api, from_private_key, to_public_key, signing_key_pair) = match args {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+
+    let promise = cx
+        .task(move || {
+            api.generate_transform_key(&from_private_key, &to_public_key, &signing_key_pair)
+        })
+        .promise(|mut cx, result| {
+            let transform_key = util::recrypt_result_or_throw(&mut cx, result)?;
+            util::transform_key_to_js_object(&mut cx, transform_key)
+        });
+
+    Ok(promise)
+}
+
 pub fn api256_compute_public_key(mut cx: FunctionContext) -> JsResult<JsObject> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
-    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
 
-    let derived_public_key = recrypt_api_256
-        .api
-        .compute_public_key(&util::buffer_to_private_key(&cx, private_key_buffer))
-        .unwrap();
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
+
+    let derived_public_key = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256.api.compute_public_key(&private_key),
+    )?;
 
     util::public_key_to_js_object(&mut cx, &derived_public_key)
 }
@@ -152,90 +680,799 @@ pub fn api256_derive_symmetric_key(mut cx: FunctionContext) -> JsResult<JsBuffer
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
     let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
 
-    let decrypted_symmetric_key = recrypt_api_256
-        .api
-        .derive_symmetric_key(&util::buffer_to_plaintext(&cx, plaintext_buffer));
+    let plaintext = util::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+
+    let decrypted_symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
 
     util::bytes_to_buffer(&mut cx, decrypted_symmetric_key.bytes())
 }
 
+/// Derive a private key from the provided 384 byte secret, such as a decrypted Plaintext, by hashing it. The same secret
+/// always gives the same private key. If the `handle` option is set the key is returned as a PrivateKeyHandle.
+pub fn api256_derive_private_key(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let secret_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(2);
+
+    let secret = util::buffer_to_plaintext(&mut cx, secret_buffer, "secretBytes")?;
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+    let private_key = recrypt_api_256.api.derive_private_key(&secret);
+
+    handles::private_key_to_js_value(&mut cx, private_key, as_handle)
+}
+
 pub fn api256_encrypt(mut cx: FunctionContext) -> JsResult<JsObject> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
     let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
     let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
-    let private_signing_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
 
-    let public_key = util::js_object_to_public_key(&mut cx, to_public_key_obj);
-    let signing_key_pair = SigningKeypair::from_bytes(&util::buffer_to_fixed_64_bytes(
-        &cx,
-        private_signing_key_buffer,
+    let public_key = util::js_object_to_public_key(&mut cx, to_public_key_obj, "toPublicKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
         "privateSigningKey",
-    ))
-    .unwrap();
-
-    let encrypted_value = recrypt_api_256
-        .api
-        .encrypt(
-            &util::buffer_to_plaintext(&cx, plaintext_buffer),
-            &public_key,
-            &signing_key_pair,
-        )
-        .unwrap();
+    )?;
+
+    let plaintext = util::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+
+    let encrypted_value = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256
+            .api
+            .encrypt(&plaintext, &public_key, &signing_key_pair),
+    )?;
 
     util::encrypted_value_to_js_object(&mut cx, encrypted_value)
 }
 
+/// Encrypt the same plaintext to each of the provided public keys. The plaintext and signing key are only parsed once,
+/// and every public key is parsed before any encryption is done. Returns an array with one entry per public key, in
+/// order, which is either the EncryptedValue object or the error that occurred for that recipient.
+pub fn api256_encrypt_many(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let to_public_keys_array: Handle<JsArray> = cx.argument::<JsArray>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+    let plaintext = util::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+
+    let to_public_keys = util::js_array_to_batch(
+        &mut cx,
+        to_public_keys_array,
+        "toPublicKeys",
+        util::js_object_to_public_key,
+    )?;
+
+    let api = &recrypt_api_256.api;
+    let results = run_batch(to_public_keys, false, |public_key| {
+        api.encrypt(&plaintext, &public_key, &signing_key_pair)
+    });
+
+    util::batch_results_to_js_array(&mut cx, results, |cx, encrypted_value| {
+        Ok(util::encrypted_value_to_js_object(cx, encrypted_value)?.upcast())
+    })
+}
+
+/// Same as `api256_encrypt`, but the encryption is run on the libuv threadpool. Returns a Promise which resolves with the
+/// EncryptedValue object.
+pub fn api256_encrypt_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let args = util::parse_or_reject(&mut cx, |cx| {
+        let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+        let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+        let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+        let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+        let public_key = util::js_object_to_public_key(cx, to_public_key_obj, "toPublicKey")?;
+        let signing_key_pair = handles::js_value_to_signing_key_pair(
+            cx,
+            private_signing_key_value,
+            "privateSigningKey",
+        )?;
+        let plaintext = util::buffer_to_plaintext(cx, plaintext_buffer, "plaintext")?;
+        Ok((
+            recrypt_api_256.api.clone(),
+            plaintext,
+            public_key,
+            signing_key_pair,
+        ))
+    });
+    let (api, plaintext, public_key, signing_key_pair) = match args {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+
+    let promise = cx
+        .task(move || api.encrypt(&plaintext, &public_key, &signing_key_pair))
+        .promise(|mut cx, result| {
+            let encrypted_value = util::recrypt_result_or_throw(&mut cx, result)?;
+            util::encrypted_value_to_js_object(&mut cx, encrypted_value)
+        });
+
+    Ok(promise)
+}
+
 pub fn api256_transform(mut cx: FunctionContext) -> JsResult<JsObject> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
     let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
     let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
-    let private_signing_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
-
-    let encrypted_value = util::js_object_to_encrypted_value(&mut cx, encrypted_value_obj);
-    let transform_key = util::js_object_to_transform_key(&mut cx, transform_key_obj);
-    let signing_key_pair = SigningKeypair::from_bytes(&util::buffer_to_fixed_64_bytes(
-        &cx,
-        private_signing_key_buffer,
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+    let encrypted_value =
+        util::js_object_to_encrypted_value(&mut cx, encrypted_value_obj, "encryptedValue")?;
+    let transform_key =
+        util::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
         "privateSigningKey",
-    ))
-    .unwrap();
+    )?;
 
-    let transformed_encrypted_value = recrypt_api_256
-        .api
-        .transform(encrypted_value, transform_key, &signing_key_pair)
-        .unwrap();
+    let transformed_encrypted_value = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256
+            .api
+            .transform(encrypted_value, transform_key, &signing_key_pair),
+    )?;
 
     util::encrypted_value_to_js_object(&mut cx, transformed_encrypted_value)
 }
 
+/// Transform each of the provided encrypted values with the same transform key. The transform key and signing key are
+/// only parsed once. If the optional `options.parallel` flag is set the transforms are spread across all CPU cores.
+/// Returns an array with one entry per encrypted value, in order, which is either the transformed EncryptedValue object
+/// or the error that occurred for that value.
+pub fn api256_transform_many(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let encrypted_values_array: Handle<JsArray> = cx.argument::<JsArray>(1)?;
+    let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(4);
+
+    let transform_key =
+        util::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+    let parallel = util::get_bool_option(&mut cx, options, "parallel")?;
+    let encrypted_values = util::js_array_to_batch(
+        &mut cx,
+        encrypted_values_array,
+        "encryptedValues",
+        util::js_object_to_encrypted_value,
+    )?;
+
+    let api = &recrypt_api_256.api;
+    let results = run_batch(encrypted_values, parallel, |encrypted_value| {
+        api.transform(encrypted_value, transform_key.clone(), &signing_key_pair)
+    });
+
+    util::batch_results_to_js_array(&mut cx, results, |cx, encrypted_value| {
+        Ok(util::encrypted_value_to_js_object(cx, encrypted_value)?.upcast())
+    })
+}
+
+/// Same as `api256_transform`, but the transform is run on the libuv threadpool. Returns a Promise which resolves with
+/// the transformed EncryptedValue object.
+pub fn api256_transform_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let args = util::parse_or_reject(&mut cx, |cx| {
+        let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+        let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+        let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+        let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+        let encrypted_value =
+            util::js_object_to_encrypted_value(cx, encrypted_value_obj, "encryptedValue")?;
+        let transform_key =
+            util::js_object_to_transform_key(cx, transform_key_obj, "transformKey")?;
+        let signing_key_pair = handles::js_value_to_signing_key_pair(
+            cx,
+            private_signing_key_value,
+            "privateSigningKey",
+        )?;
+        Ok((
+            recrypt_api_256.api.clone(),
+            encrypted_value,
+            transform_key,
+            signing_key_pair,
+        ))
+    });
+    let (api, encrypted_value, transform_key, signing_key_pair) = match args {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+
+    let promise = cx
+        .task(move || api.transform(encrypted_value, transform_key, &signing_key_pair))
+        .promise(|mut cx, result| {
+            let transformed_encrypted_value = util::recrypt_result_or_throw(&mut cx, result)?;
+            util::encrypted_value_to_js_object(&mut cx, transformed_encrypted_value)
+        });
+
+    Ok(promise)
+}
+
 pub fn api256_decrypt(mut cx: FunctionContext) -> JsResult<JsBuffer> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
     let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
-    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(2)?;
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
 
-    let encrypted_value = util::js_object_to_encrypted_value(&mut cx, encrypted_value_obj);
+    let encrypted_value =
+        util::js_object_to_encrypted_value(&mut cx, encrypted_value_obj, "encryptedValue")?;
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
 
-    let decrypted_value = recrypt_api_256
-        .api
-        .decrypt(
-            encrypted_value,
-            &util::buffer_to_private_key(&cx, private_key_buffer),
-        )
-        .unwrap();
+    let decrypted_value = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256.api.decrypt(encrypted_value, &private_key),
+    )?;
 
     util::bytes_to_buffer(&mut cx, decrypted_value.bytes())
 }
 
+/// Same as `api256_decrypt`, but the decryption is run on the libuv threadpool. Returns a Promise which resolves with the
+/// decrypted Plaintext Buffer.
+pub fn api256_decrypt_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let args = util::parse_or_reject(&mut cx, |cx| {
+        let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+        let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+        let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+
+        let encrypted_value =
+            util::js_object_to_encrypted_value(cx, encrypted_value_obj, "encryptedValue")?;
+        let private_key = handles::js_value_to_private_key(cx, private_key_value, "privateKey")?;
+        Ok((recrypt_api_256.api.clone(), encrypted_value, private_key))
+    });
+    let (api, encrypted_value, private_key) = match args {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+
+    let promise = cx
+        .task(move || api.decrypt(encrypted_value, &private_key))
+        .promise(|mut cx, result| {
+            let decrypted_value = util::recrypt_result_or_throw(&mut cx, result)?;
+            util::bytes_to_buffer(&mut cx, decrypted_value.bytes())
+        });
+
+    Ok(promise)
+}
+
+/// Decrypt each of the provided encrypted values with the same private key, which is only parsed once. Takes an optional
+/// options object: if `deriveSymmetricKey` is set the symmetric key derived from each plaintext is returned instead of
+/// the plaintext itself, and if `parallel` is set the decryptions are spread across all CPU cores. Returns an array with
+/// one entry per encrypted value, in order, which is either the resulting Buffer or the error that occurred for that
+/// value.
+pub fn api256_decrypt_many(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let encrypted_values_array: Handle<JsArray> = cx.argument::<JsArray>(1)?;
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(3);
+
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
+    let derive_symmetric_key = util::get_bool_option(&mut cx, options, "deriveSymmetricKey")?;
+    let parallel = util::get_bool_option(&mut cx, options, "parallel")?;
+    let encrypted_values = util::js_array_to_batch(
+        &mut cx,
+        encrypted_values_array,
+        "encryptedValues",
+        util::js_object_to_encrypted_value,
+    )?;
+
+    let api = &recrypt_api_256.api;
+    let results = run_batch(encrypted_values, parallel, |encrypted_value| {
+        decrypt_batch_element(api, encrypted_value, &private_key, derive_symmetric_key)
+    });
+
+    util::batch_results_to_js_array(&mut cx, results, |cx, decrypted_value| {
+        Ok(util::bytes_to_buffer(cx, decrypted_value.bytes())?.upcast())
+    })
+}
+
+/// Same as `api256_decrypt_many`, but the decryptions are run on the libuv threadpool. Returns a Promise which resolves
+/// with the array of results.
+pub fn api256_decrypt_many_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let args = util::parse_or_reject(&mut cx, |cx| {
+        let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+        let encrypted_values_array: Handle<JsArray> = cx.argument::<JsArray>(1)?;
+        let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+        let options: Option<Handle<JsValue>> = cx.argument_opt(3);
+
+        let private_key = handles::js_value_to_private_key(cx, private_key_value, "privateKey")?;
+        let derive_symmetric_key = util::get_bool_option(cx, options, "deriveSymmetricKey")?;
+        let parallel = util::get_bool_option(cx, options, "parallel")?;
+        let encrypted_values = util::js_array_to_batch(
+            cx,
+            encrypted_values_array,
+            "encryptedValues",
+            util::js_object_to_encrypted_value,
+        )?;
+
+        // Conversion errors have to be rooted so they can be carried over to the completion callback.
+        let (inputs, slots) = split_batch(encrypted_values);
+        let mut rooted_slots = Vec::with_capacity(slots.len());
+        for slot in slots {
+            rooted_slots.push(match slot {
+                Ok(()) => Ok(()),
+                Err(error) => Err(error.downcast_or_throw::<JsObject, _>(cx)?.root(cx)),
+            });
+        }
+        Ok((
+            recrypt_api_256.api.clone(),
+            private_key,
+            derive_symmetric_key,
+            parallel,
+            inputs,
+            rooted_slots,
+        ))
+    });
+    let (api, private_key, derive_symmetric_key, parallel, inputs, rooted_slots) = match args {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+
+    let promise = cx
+        .task(move || {
+            map_inputs(inputs, parallel, |encrypted_value| {
+                decrypt_batch_element(&api, encrypted_value, &private_key, derive_symmetric_key)
+            })
+        })
+        .promise(move |mut cx, outputs| {
+            let slots = rooted_slots
+                .into_iter()
+                .map(|slot| slot.map_err(|root| root.into_inner(&mut cx).upcast()))
+                .collect();
+            let results = merge_batch(slots, outputs);
+            util::batch_results_to_js_array(&mut cx, results, |cx, decrypted_value| {
+                Ok(util::bytes_to_buffer(cx, decrypted_value.bytes())?.upcast())
+            })
+        });
+
+    Ok(promise)
+}
+
+/// Encrypt the provided data to the provided public key. A new Plaintext is generated and the symmetric key derived
+/// from it is used to AES-256-GCM encrypt the data, along with the optional AAD. The Plaintext is then encrypted to the
+/// public key. Returns an object with the `encryptedDek` EncryptedValue and the `ciphertext` Buffer. Neither the
+/// Plaintext nor the symmetric key ever leave Rust.
+pub fn api256_encrypt_data(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let data_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+    let aad_arg: Option<Handle<JsValue>> = cx.argument_opt(4);
+
+    let public_key = util::js_object_to_public_key(&mut cx, to_public_key_obj, "toPublicKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+    let aad =
+        match util::downcast_optional_argument::<JsBuffer, _>(&mut cx, aad_arg, "aad", "Buffer")? {
+            Some(aad_buffer) => util::buffer_to_variable_bytes(&cx, aad_buffer),
+            None => vec![],
+        };
+
+    let plaintext = recrypt_api_256.api.gen_plaintext();
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+    let ciphertext = aes::encrypt(
+        symmetric_key.bytes(),
+        &util::buffer_to_variable_bytes(&cx, data_buffer),
+        &aad,
+    );
+    let encrypted_dek = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256
+            .api
+            .encrypt(&plaintext, &public_key, &signing_key_pair),
+    )?;
+
+    let envelope = cx.empty_object();
+    let encrypted_dek_obj = util::encrypted_value_to_js_object(&mut cx, encrypted_dek)?;
+    let ciphertext_buffer = util::bytes_to_buffer(&mut cx, &ciphertext)?;
+    envelope.set(&mut cx, "encryptedDek", encrypted_dek_obj)?;
+    envelope.set(&mut cx, "ciphertext", ciphertext_buffer)?;
+    Ok(envelope)
+}
+
+/// Decrypt an envelope created by `api256_encrypt_data` with the provided private key. The optional AAD must match the
+/// AAD the data was encrypted with. Returns the decrypted data.
+pub fn api256_decrypt_data(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let envelope_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let aad_arg: Option<Handle<JsValue>> = cx.argument_opt(3);
+
+    let encrypted_dek_obj =
+        util::get_field(&mut cx, envelope_obj, "envelope", "encryptedDek", "object")?;
+    let ciphertext_buffer: Handle<JsBuffer> =
+        util::get_field(&mut cx, envelope_obj, "envelope", "ciphertext", "Buffer")?;
+    let encrypted_dek =
+        util::js_object_to_encrypted_value(&mut cx, encrypted_dek_obj, "envelope.encryptedDek")?;
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
+    let aad =
+        match util::downcast_optional_argument::<JsBuffer, _>(&mut cx, aad_arg, "aad", "Buffer")? {
+            Some(aad_buffer) => util::buffer_to_variable_bytes(&cx, aad_buffer),
+            None => vec![],
+        };
+
+    let plaintext = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256.api.decrypt(encrypted_dek, &private_key),
+    )?;
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+    let ciphertext = util::buffer_to_variable_bytes(&cx, ciphertext_buffer);
+    let data = aes::aes_result_or_throw(
+        &mut cx,
+        aes::decrypt(symmetric_key.bytes(), &ciphertext, &aad),
+        "envelope.ciphertext",
+    )?;
+
+    util::bytes_to_buffer(&mut cx, &data)
+}
+
+/// Encrypt the provided data once for many recipients. A new Plaintext is generated and the symmetric key derived from
+/// it is used to AES-256-GCM encrypt the data, along with the optional AAD. The Plaintext is then encrypted to the public
+/// key of each recipient in the `{recipientId: publicKey}` object. Returns a shared document object with the `ciphertext`
+/// Buffer and an `encryptedDeks` object mapping each recipient id to its EncryptedValue.
+pub fn api256_create_shared_document(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let data_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let recipients_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+    let aad_arg: Option<Handle<JsValue>> = cx.argument_opt(4);
+
+    let mut recipients = vec![];
+    for (recipient_id, value) in util::js_object_entries(&mut cx, recipients_obj)? {
+        let field_path = format!("recipients.{}", recipient_id);
+        let public_key_obj: Handle<JsObject> =
+            util::downcast_field(&mut cx, value, &field_path, "object")?;
+        let public_key = util::js_object_to_public_key(&mut cx, public_key_obj, &field_path)?;
+        recipients.push((recipient_id, public_key));
+    }
+    if recipients.is_empty() {
+        return util::throw_error(
+            &mut cx,
+            util::ErrorKind::RangeError,
+            util::ErrorCode::EmptyArray,
+            Some("recipients"),
+            "Provided object for 'recipients' must have at least one recipient.",
+        );
+    }
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+    let aad =
+        match util::downcast_optional_argument::<JsBuffer, _>(&mut cx, aad_arg, "aad", "Buffer")? {
+            Some(aad_buffer) => util::buffer_to_variable_bytes(&cx, aad_buffer),
+            None => vec![],
+        };
+
+    let plaintext = recrypt_api_256.api.gen_plaintext();
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+    let ciphertext = aes::encrypt(
+        symmetric_key.bytes(),
+        &util::buffer_to_variable_bytes(&cx, data_buffer),
+        &aad,
+    );
+    let encrypted_deks = util::empty_dictionary_object(&mut cx)?;
+    for (recipient_id, public_key) in recipients {
+        let encrypted_dek = util::recrypt_result_or_throw(
+            &mut cx,
+            recrypt_api_256
+                .api
+                .encrypt(&plaintext, &public_key, &signing_key_pair),
+        )?;
+        let encrypted_dek_obj = util::encrypted_value_to_js_object(&mut cx, encrypted_dek)?;
+        encrypted_deks.set(&mut cx, recipient_id.as_str(), encrypted_dek_obj)?;
+    }
+
+    let ciphertext_buffer = util::bytes_to_buffer(&mut cx, &ciphertext)?;
+    shared_document_to_js_object(&mut cx, ciphertext_buffer, encrypted_deks)
+}
+
+/// Decrypt a shared document created by `api256_create_shared_document` as the provided recipient. The optional AAD must
+/// match the AAD the data was encrypted with. Returns the decrypted data.
+pub fn api256_decrypt_shared_document(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let document_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    let recipient_id = cx.argument::<JsString>(2)?.value(&mut cx);
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+    let aad_arg: Option<Handle<JsValue>> = cx.argument_opt(4);
+
+    let (ciphertext_buffer, encrypted_deks) = js_object_to_shared_document(&mut cx, document_obj)?;
+    let encrypted_dek = recipient_encrypted_dek(&mut cx, encrypted_deks, &recipient_id)?;
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
+    let aad =
+        match util::downcast_optional_argument::<JsBuffer, _>(&mut cx, aad_arg, "aad", "Buffer")? {
+            Some(aad_buffer) => util::buffer_to_variable_bytes(&cx, aad_buffer),
+            None => vec![],
+        };
+
+    let plaintext = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256.api.decrypt(encrypted_dek, &private_key),
+    )?;
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+    let ciphertext = util::buffer_to_variable_bytes(&cx, ciphertext_buffer);
+    let data = aes::aes_result_or_throw(
+        &mut cx,
+        aes::decrypt(symmetric_key.bytes(), &ciphertext, &aad),
+        "document.ciphertext",
+    )?;
+
+    util::bytes_to_buffer(&mut cx, &data)
+}
+
+/// Give a new recipient access to a shared document without re-encrypting its data. The author's private key must be
+/// able to decrypt one of the document's encrypted DEKs, which is decrypted and encrypted to the recipient's public key.
+/// Returns a new shared document with the recipient's encrypted DEK added, replacing any it already had.
+pub fn api256_grant_access(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let document_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    let recipient_id = cx.argument::<JsString>(2)?.value(&mut cx);
+    let public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(3)?;
+    let author_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(4)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(5)?;
+
+    let (ciphertext_buffer, encrypted_deks) = js_object_to_shared_document(&mut cx, document_obj)?;
+    let public_key = util::js_object_to_public_key(&mut cx, public_key_obj, "publicKey")?;
+    let author_private_key =
+        handles::js_value_to_private_key(&mut cx, author_private_key_value, "authorPrivateKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+
+    // Encrypted values don't record who they were encrypted to, so try the author's key against each of them.
+    let entries = util::js_object_entries(&mut cx, encrypted_deks)?;
+    let mut plaintext = None;
+    for (existing_id, value) in &entries {
+        let field_path = format!("document.encryptedDeks.{}", existing_id);
+        let encrypted_dek_obj: Handle<JsObject> =
+            util::downcast_field(&mut cx, *value, &field_path, "object")?;
+        let encrypted_dek =
+            util::js_object_to_encrypted_value(&mut cx, encrypted_dek_obj, &field_path)?;
+        if let Ok(decrypted) = recrypt_api_256
+            .api
+            .decrypt(encrypted_dek, &author_private_key)
+        {
+            plaintext = Some(decrypted);
+            break;
+        }
+    }
+    let plaintext = match plaintext {
+        Some(plaintext) => plaintext,
+        None => {
+            return util::throw_error(
+                &mut cx,
+                util::ErrorKind::Error,
+                util::ErrorCode::DecryptFailed,
+                Some("authorPrivateKey"),
+                "Provided value for 'authorPrivateKey' can't decrypt any of the document's encrypted DEKs.",
+            );
+        }
+    };
+    let encrypted_dek = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256
+            .api
+            .encrypt(&plaintext, &public_key, &signing_key_pair),
+    )?;
+
+    let new_encrypted_deks = util::empty_dictionary_object(&mut cx)?;
+    for (existing_id, value) in entries {
+        if existing_id != recipient_id {
+            new_encrypted_deks.set(&mut cx, existing_id.as_str(), value)?;
+        }
+    }
+    let encrypted_dek_obj = util::encrypted_value_to_js_object(&mut cx, encrypted_dek)?;
+    new_encrypted_deks.set(&mut cx, recipient_id.as_str(), encrypted_dek_obj)?;
+    shared_document_to_js_object(&mut cx, ciphertext_buffer, new_encrypted_deks)
+}
+
+/// Remove a recipient's access to a shared document by dropping its encrypted DEK. The data isn't re-encrypted, so this
+/// doesn't protect against a recipient who already decrypted the DEK. Returns a new shared document.
+pub fn api256_revoke_access(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let document_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let recipient_id = cx.argument::<JsString>(1)?.value(&mut cx);
+
+    let (ciphertext_buffer, encrypted_deks) = js_object_to_shared_document(&mut cx, document_obj)?;
+    // Make sure the recipient had access, so a mistyped id doesn't silently leave access in place.
+    recipient_encrypted_dek(&mut cx, encrypted_deks, &recipient_id)?;
+
+    let new_encrypted_deks = util::empty_dictionary_object(&mut cx)?;
+    for (existing_id, value) in util::js_object_entries(&mut cx, encrypted_deks)? {
+        if existing_id != recipient_id {
+            new_encrypted_deks.set(&mut cx, existing_id.as_str(), value)?;
+        }
+    }
+    shared_document_to_js_object(&mut cx, ciphertext_buffer, new_encrypted_deks)
+}
+
+/// Encrypt the provided data with the symmetric key derived from the provided Plaintext into the IronCore document
+/// format documented in `document.rs`, with the provided `{documentId, segmentId}` header.
+pub fn api256_encrypt_document(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let data_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(2)?;
+    let header_obj: Handle<JsObject> = cx.argument::<JsObject>(3)?;
+
+    let plaintext = util::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+    let header = document::js_object_to_document_header(&mut cx, header_obj, "header")?;
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+    let data = util::buffer_to_variable_bytes(&cx, data_buffer);
+
+    util::bytes_to_buffer(
+        &mut cx,
+        &document::encrypt_document(&header, &symmetric_key, &data),
+    )
+}
+
+/// Decrypt a document in the IronCore document format with the symmetric key derived from the provided Plaintext.
+/// Returns an object with the parsed `header` and the decrypted `data`.
+pub fn api256_decrypt_document(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let document_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(2)?;
+
+    let plaintext = util::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+    let document_bytes = util::buffer_to_variable_bytes(&cx, document_buffer);
+    let (header, encrypted) = serialization::serialization_result_or_throw(
+        &mut cx,
+        document::parse_document(&document_bytes, "encryptedDocument"),
+    )?;
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+    let data = aes::aes_result_or_throw(
+        &mut cx,
+        aes::decrypt(symmetric_key.bytes(), encrypted, &[]),
+        "encryptedDocument",
+    )?;
+
+    let result = cx.empty_object();
+    let header_obj = document::document_header_to_js_object(&mut cx, header)?;
+    let data_buffer = util::bytes_to_buffer(&mut cx, &data)?;
+    result.set(&mut cx, "header", header_obj)?;
+    result.set(&mut cx, "data", data_buffer)?;
+    Ok(result)
+}
+
+/// Create a handle which encrypts data in the chunked stream format with the symmetric key derived from the provided
+/// Plaintext. See the `stream` module for the format.
+pub fn api256_create_stream_encryptor(
+    mut cx: FunctionContext,
+) -> JsResult<JsBox<StreamEncryptorHandle>> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+
+    let plaintext = util::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+
+    Ok(cx.boxed(StreamEncryptorHandle(RefCell::new(
+        stream::StreamEncryptor::new(&symmetric_key),
+    ))))
+}
+
+/// Create a handle which decrypts a stream created by a stream encryptor for the same Plaintext.
+pub fn api256_create_stream_decryptor(
+    mut cx: FunctionContext,
+) -> JsResult<JsBox<StreamDecryptorHandle>> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+
+    let plaintext = util::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+    let symmetric_key = recrypt_api_256.api.derive_symmetric_key(&plaintext);
+
+    Ok(cx.boxed(StreamDecryptorHandle(RefCell::new(
+        stream::StreamDecryptor::new(&symmetric_key),
+    ))))
+}
+
+/// Encrypt a private key under a passphrase into the versioned backup blob documented in `passphrase.rs`. The key may be
+/// a recrypt private key (a 32 byte Buffer or a PrivateKeyHandle) or an Ed25519 signing key (a 64 byte Buffer or a
+/// SigningKeyPairHandle). Its public key is computed and stored in the blob so that it can be checked on import.
+pub fn api256_export_private_key(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let passphrase_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let params_arg: Option<Handle<JsValue>> = cx.argument_opt(3);
+
+    let is_signing_key = handles::is_signing_key_pair_handle(&mut cx, private_key_value)
+        || match private_key_value.downcast::<JsBuffer, _>(&mut cx) {
+            Ok(buffer) => buffer.as_slice(&cx).len() == 64,
+            Err(_) => false,
+        };
+    let (key_type, private_key_bytes, public_key_bytes) = if is_signing_key {
+        let signing_key_pair =
+            handles::js_value_to_signing_key_pair(&mut cx, private_key_value, "privateKey")?;
+        (
+            passphrase::BackupKeyType::Ed25519,
+            Zeroizing::new(signing_key_pair.bytes().to_vec()),
+            signing_key_pair.public_key().bytes().to_vec(),
+        )
+    } else {
+        let private_key =
+            handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
+        let public_key = util::recrypt_result_or_throw(
+            &mut cx,
+            recrypt_api_256.api.compute_public_key(&private_key),
+        )?;
+        let (x, y) = public_key.bytes_x_y();
+        (
+            passphrase::BackupKeyType::Recrypt256,
+            Zeroizing::new(private_key.bytes().to_vec()),
+            [&x[..], &y[..]].concat(),
+        )
+    };
+    let passphrase = passphrase::js_value_to_passphrase(&mut cx, passphrase_value, "passphrase")?;
+    let params = passphrase::js_value_to_kdf_params(&mut cx, params_arg, "params")?;
+
+    let blob = passphrase::export_key(
+        key_type,
+        &private_key_bytes,
+        &public_key_bytes,
+        &passphrase,
+        params,
+    );
+    util::bytes_to_buffer(&mut cx, &blob)
+}
+
+/// Decrypt a backup blob created by `api256_export_private_key` with the passphrase. The decrypted key is checked against
+/// the public key stored in the blob by recomputing its public key. Returns an object with the `keyType` ("recrypt" or
+/// "ed25519"), the `privateKey` and the `publicKey`. If the `handle` option is set the private key is returned as a
+/// handle instead of a Buffer.
+pub fn api256_import_private_key(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let args = import_private_key_args(&mut cx)?;
+
+    let imported = passphrase::import_result_or_throw(
+        &mut cx,
+        passphrase::import_key(&args.blob, &args.passphrase, "blob"),
+        "blob",
+    )?;
+    imported_key_to_js_object(&mut cx, &args.api, imported, args.as_handle)
+}
+
+/// Same as `api256_import_private_key`, but Argon2id and the decryption are run on the libuv threadpool. Returns a
+/// Promise which resolves with the imported key object.
+pub fn api256_import_private_key_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let args = match util::parse_or_reject(&mut cx, import_private_key_args) {
+        Ok(args) => args,
+        Err(rejected) => return Ok(rejected),
+    };
+    let api = args.api.clone();
+    let as_handle = args.as_handle;
+
+    let promise = cx
+        .task(move || passphrase::import_key(&args.blob, &args.passphrase, "blob"))
+        .promise(move |mut cx, imported| {
+            let imported = passphrase::import_result_or_throw(&mut cx, imported, "blob")?;
+            imported_key_to_js_object(&mut cx, &api, imported, as_handle)
+        });
+
+    Ok(promise)
+}
+
 pub fn api256_schnorr_sign(mut cx: FunctionContext) -> JsResult<JsBuffer> {
     let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
-    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
     let public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
     let message_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
 
-    let public_key = util::js_object_to_public_key(&mut cx, public_key_obj);
+    let public_key = util::js_object_to_public_key(&mut cx, public_key_obj, "publicKey")?;
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
 
     let signature = recrypt_api_256.api.schnorr_sign(
-        &util::buffer_to_private_key(&cx, private_key_buffer),
+        &private_key,
         &public_key,
         &util::buffer_to_variable_bytes(&cx, message_buffer),
     );
@@ -251,8 +1488,8 @@ pub fn api256_schnorr_verify(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     let message_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
     let signature_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(4)?;
 
-    let public_key = util::js_object_to_public_key(&mut cx, public_key_obj);
-    let signature = util::buffer_to_schnorr_signature(&cx, signature_buffer);
+    let public_key = util::js_object_to_public_key(&mut cx, public_key_obj, "publicKey")?;
+    let signature = util::buffer_to_schnorr_signature(&mut cx, signature_buffer, "signature")?;
 
     let augmented_private_key = {
         //Ignore both null or undefined as values are passed for augmented private key
@@ -261,10 +1498,11 @@ pub fn api256_schnorr_verify(mut cx: FunctionContext) -> JsResult<JsBoolean> {
         {
             None
         } else {
-            let casted_private_key_buffer = augmented_private_key_buffer
-                .downcast::<JsBuffer, _>(&mut cx)
-                .unwrap();
-            Some(util::buffer_to_private_key(&cx, casted_private_key_buffer))
+            Some(handles::js_value_to_private_key(
+                &mut cx,
+                augmented_private_key_buffer,
+                "augmentedPrivateKey",
+            )?)
         }
     };
 
@@ -278,6 +1516,156 @@ pub fn api256_schnorr_verify(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     Ok(cx.boolean(verified))
 }
 
+/// Create the server half of an augmented key pair for a user. Takes the user's key pair object, of which only the
+/// `publicKey` is required; if a `privateKey` is also provided it must match the public key. A fresh augmentation
+/// private key is generated for the server to keep, and the combined public key, which is the user's public key
+/// augmented with the augmentation public key, is what values should be encrypted to. Takes an optional options object:
+/// if `handle` is set the augmentation private key is returned as a PrivateKeyHandle. Returns
+/// `{augmentationPrivateKey, combinedPublicKey}`.
+pub fn api256_create_augmented_key_pair(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let user_key_pair_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(2);
+
+    let user_public_key_obj: Handle<JsObject> = util::get_field(
+        &mut cx,
+        user_key_pair_obj,
+        "userKeyPair",
+        "publicKey",
+        "object",
+    )?;
+    let user_public_key =
+        util::js_object_to_public_key(&mut cx, user_public_key_obj, "userKeyPair.publicKey")?;
+    let user_private_key_value = user_key_pair_obj.get_value(&mut cx, "privateKey")?;
+    if !user_private_key_value.is_a::<JsUndefined, _>(&mut cx) {
+        let user_private_key = handles::js_value_to_private_key(
+            &mut cx,
+            user_private_key_value,
+            "userKeyPair.privateKey",
+        )?;
+        let computed_public_key = util::recrypt_result_or_throw(
+            &mut cx,
+            recrypt_api_256.api.compute_public_key(&user_private_key),
+        )?;
+        if computed_public_key != user_public_key {
+            return util::throw_error(
+                &mut cx,
+                util::ErrorKind::Error,
+                util::ErrorCode::PublicKeyMismatch,
+                Some("userKeyPair.publicKey"),
+                "Provided public key for 'userKeyPair.publicKey' doesn't match the public key of 'userKeyPair.privateKey'.",
+            );
+        }
+    }
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+
+    let (augmentation_private_key, augmentation_public_key) =
+        util::recrypt_result_or_throw(&mut cx, recrypt_api_256.api.generate_key_pair())?;
+    let combined_public_key =
+        util::recrypt_result_or_throw(&mut cx, user_public_key.augment(&augmentation_public_key))?;
+
+    let augmented_key_pair = cx.empty_object();
+    let augmentation_private_key_value =
+        handles::private_key_to_js_value(&mut cx, augmentation_private_key, as_handle)?;
+    let combined_public_key_obj = util::public_key_to_js_object(&mut cx, &combined_public_key)?;
+    augmented_key_pair.set(
+        &mut cx,
+        "augmentationPrivateKey",
+        augmentation_private_key_value,
+    )?;
+    augmented_key_pair.set(&mut cx, "combinedPublicKey", combined_public_key_obj)?;
+    Ok(augmented_key_pair)
+}
+
+/// Generate a transform key from the user's private key and augment it with the server's augmentation private key, so
+/// it transforms values encrypted to the combined public key. Equivalent to `generateTransformKey` followed by
+/// `augmentTransformKey256`.
+pub fn api256_generate_augmented_transform_key(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let user_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let augmentation_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(3)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(4)?;
+
+    let user_private_key =
+        handles::js_value_to_private_key(&mut cx, user_private_key_value, "userPrivateKey")?;
+    let augmentation_private_key = handles::js_value_to_private_key(
+        &mut cx,
+        augmentation_private_key_value,
+        "augmentationPrivateKey",
+    )?;
+    let to_public_key = util::js_object_to_public_key(&mut cx, to_public_key_obj, "toPublicKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+
+    let transform_key = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_256.api.generate_transform_key(
+            &user_private_key,
+            &to_public_key,
+            &signing_key_pair,
+        ),
+    )?;
+    let augmented_transform_key =
+        util::recrypt_result_or_throw(&mut cx, transform_key.augment(&augmentation_private_key))?;
+
+    util::transform_key_to_js_object(&mut cx, augmented_transform_key)
+}
+
+/// Client half of the proof of possession for a combined public key. Signs the message with the user's private key,
+/// bound to the combined public key, so the server can check it with `verifyAugmentedKeySignature`.
+pub fn api256_sign_for_augmented_key(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let user_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let combined_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let message_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
+
+    let user_private_key =
+        handles::js_value_to_private_key(&mut cx, user_private_key_value, "userPrivateKey")?;
+    let combined_public_key =
+        util::js_object_to_public_key(&mut cx, combined_public_key_obj, "combinedPublicKey")?;
+
+    let signature = recrypt_api_256.api.schnorr_sign(
+        &user_private_key,
+        &combined_public_key,
+        &util::buffer_to_variable_bytes(&cx, message_buffer),
+    );
+
+    util::bytes_to_buffer(&mut cx, signature.bytes())
+}
+
+/// Server half of the proof of possession for a combined public key. Verifies a signature from
+/// `signForAugmentedKey` using the server's augmentation private key, which shows the signer holds the user's half of
+/// the combined key.
+pub fn api256_verify_augmented_key_signature(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let combined_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    let augmentation_private_key_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let message_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
+    let signature_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(4)?;
+
+    let combined_public_key =
+        util::js_object_to_public_key(&mut cx, combined_public_key_obj, "combinedPublicKey")?;
+    let augmentation_private_key = handles::js_value_to_private_key(
+        &mut cx,
+        augmentation_private_key_value,
+        "augmentationPrivateKey",
+    )?;
+    let signature = util::buffer_to_schnorr_signature(&mut cx, signature_buffer, "signature")?;
+
+    let verified = recrypt_api_256.api.schnorr_verify(
+        &combined_public_key,
+        Some(&augmentation_private_key),
+        &util::buffer_to_variable_bytes(&cx, message_buffer),
+        signature,
+    );
+
+    Ok(cx.boolean(verified))
+}
+
 // declare_types! {
 //     pub class Api256 for RecryptApi256 {
 //         init(_cx) {}
@@ -310,29 +1698,55 @@ pub fn api256_schnorr_verify(mut cx: FunctionContext) -> JsResult<JsBoolean> {
 /// Augment the provided transform key with the provided private key. Returns an augmented TransformKey object.
 pub fn augment_transform_key_256(mut cx: FunctionContext) -> JsResult<JsObject> {
     let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
-    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
-    let transform_key = util::js_object_to_transform_key(&mut cx, transform_key_obj);
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let transform_key =
+        util::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
 
-    let augmented_transform_key = transform_key
-        .augment(&util::buffer_to_private_key(&cx, private_key_buffer))
-        .unwrap();
+    let augmented_transform_key =
+        util::recrypt_result_or_throw(&mut cx, transform_key.augment(&private_key))?;
 
     util::transform_key_to_js_object(&mut cx, augmented_transform_key)
 }
 
+/// Augment each of a user's outgoing transform keys with the augmentation factor from `api256_rotate_user_private_key`,
+/// so they transform from the rotated private key. Returns the augmented TransformKey objects in the same order. Throws
+/// if any of the transform keys is invalid, so a partially rotated set is never returned.
+pub fn apply_rotation_to_transform_keys(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let transform_keys_array: Handle<JsArray> = cx.argument::<JsArray>(0)?;
+    let augmentation_factor_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+
+    let augmentation_factor =
+        handles::js_value_to_private_key(&mut cx, augmentation_factor_value, "augmentationFactor")?;
+    let transform_key_values = transform_keys_array.to_vec(&mut cx)?;
+    let augmented_transform_keys = cx.empty_array();
+    for (i, transform_key_value) in transform_key_values.into_iter().enumerate() {
+        let transform_key_path = format!("transformKeys[{}]", i);
+        let transform_key_obj: Handle<JsObject> =
+            util::downcast_field(&mut cx, transform_key_value, &transform_key_path, "object")?;
+        let transform_key =
+            util::js_object_to_transform_key(&mut cx, transform_key_obj, &transform_key_path)?;
+        let augmented_transform_key =
+            util::recrypt_result_or_throw(&mut cx, transform_key.augment(&augmentation_factor))?;
+        let augmented_transform_key_obj =
+            util::transform_key_to_js_object(&mut cx, augmented_transform_key)?;
+        augmented_transform_keys.set(&mut cx, i as u32, augmented_transform_key_obj)?;
+    }
+    Ok(augmented_transform_keys)
+}
+
 /// Augment the provided public key with the other provided public key. Returns a new augmented PublicKey object.
 pub fn augment_public_key_256(mut cx: FunctionContext) -> JsResult<JsObject> {
     let current_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
     let other_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
 
-    let current_public_key = util::js_object_to_public_key(&mut cx, current_public_key_obj);
+    let current_public_key =
+        util::js_object_to_public_key(&mut cx, current_public_key_obj, "publicKey")?;
+    let other_public_key =
+        util::js_object_to_public_key(&mut cx, other_public_key_obj, "otherPublicKey")?;
 
-    let augmented_public_key = current_public_key
-        .augment(&util::js_object_to_public_key(
-            &mut cx,
-            other_public_key_obj,
-        ))
-        .unwrap();
+    let augmented_public_key =
+        util::recrypt_result_or_throw(&mut cx, current_public_key.augment(&other_public_key))?;
 
     util::public_key_to_js_object(&mut cx, &augmented_public_key)
 }
@@ -341,27 +1755,489 @@ pub fn augment_public_key_256(mut cx: FunctionContext) -> JsResult<JsObject> {
 /// in a specific order in order for transform keys to be signed over.
 pub fn transform_key_to_bytes_256(mut cx: FunctionContext) -> JsResult<JsBuffer> {
     let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
-    let transform_key = util::js_object_to_transform_key(&mut cx, transform_key_obj);
+    let transform_key =
+        util::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
 
     util::bytes_to_buffer(&mut cx, &transform_key.to_bytes())
 }
 
-/// Add the two provided private keys together. Used when performing key rotation.
-pub fn add_private_keys(mut cx: FunctionContext) -> JsResult<JsBuffer> {
-    let pub_key_a: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
-    let pub_key_b: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
-    let augmented = util::buffer_to_private_key(&cx, pub_key_a)
-        .augment_plus(&util::buffer_to_private_key(&cx, pub_key_b));
+/// Compress the provided public key into 33 bytes: a prefix byte which records whether y is odd, followed by x.
+pub fn compress_public_key(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let public_key = util::js_object_to_public_key(&mut cx, public_key_obj, "publicKey")?;
 
-    util::bytes_to_buffer(&mut cx, &augmented.to_bytes())
+    util::bytes_to_buffer(&mut cx, &compression::compress_public_key(&public_key))
 }
 
-/// Subtract the second provided private key from the first provided private key. Used when performing key rotation
-pub fn subtract_private_keys(mut cx: FunctionContext) -> JsResult<JsBuffer> {
-    let pub_key_a: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
-    let pub_key_b: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
-    let augmented = util::buffer_to_private_key(&cx, pub_key_a)
-        .augment_minus(&util::buffer_to_private_key(&cx, pub_key_b));
+/// Decompress the provided 33 byte compressed public key into a PublicKey object. Throws if the bytes aren't a valid
+/// point on the curve.
+pub fn decompress_public_key(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let compressed_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let public_key =
+        util::js_object_to_public_key(&mut cx, compressed_buffer.upcast(), "compressedPublicKey")?;
+
+    util::public_key_to_js_object(&mut cx, &public_key)
+}
+
+/// Serialize the provided EncryptedValue object into the versioned binary layout documented in `serialization.rs`.
+pub fn encrypted_value_to_bytes(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let encrypted_value =
+        util::js_object_to_encrypted_value(&mut cx, encrypted_value_obj, "encryptedValue")?;
+
+    let bytes = serialization::serialization_result_or_throw(
+        &mut cx,
+        serialization::encrypted_value_to_bytes(&encrypted_value, "encryptedValue"),
+    )?;
+
+    util::bytes_to_buffer(&mut cx, &bytes)
+}
+
+/// Parse an EncryptedValue object out of bytes created by `encrypted_value_to_bytes`.
+pub fn encrypted_value_from_bytes(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let bytes_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let bytes = util::buffer_to_variable_bytes(&cx, bytes_buffer);
+
+    let encrypted_value = serialization::serialization_result_or_throw(
+        &mut cx,
+        serialization::encrypted_value_from_bytes(&bytes, "bytes"),
+    )?;
+
+    util::encrypted_value_to_js_object(&mut cx, encrypted_value)
+}
+
+/// Serialize the provided TransformKey object into the versioned binary layout documented in `serialization.rs`. Unlike
+/// `transform_key_to_bytes_256` the result contains every field and can be parsed back with `transform_key_from_bytes`.
+pub fn transform_key_to_bytes(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let transform_key =
+        util::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
+
+    util::bytes_to_buffer(
+        &mut cx,
+        &serialization::transform_key_to_bytes(&transform_key),
+    )
+}
+
+/// Parse a TransformKey object out of bytes created by `transform_key_to_bytes`.
+pub fn transform_key_from_bytes(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let bytes_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let bytes = util::buffer_to_variable_bytes(&cx, bytes_buffer);
+
+    let transform_key = serialization::serialization_result_or_throw(
+        &mut cx,
+        serialization::transform_key_from_bytes(&bytes, "bytes"),
+    )?;
+
+    util::transform_key_to_js_object(&mut cx, transform_key)
+}
+
+/// Parse the `{documentId, segmentId}` header off the front of a document in the IronCore document format without
+/// decrypting it, so the caller can look up the key it needs.
+pub fn parse_document_header(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let document_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let document_bytes = util::buffer_to_variable_bytes(&cx, document_buffer);
+
+    let (header, _) = serialization::serialization_result_or_throw(
+        &mut cx,
+        document::parse_document(&document_bytes, "encryptedDocument"),
+    )?;
+
+    document::document_header_to_js_object(&mut cx, header)
+}
+
+/// Add the two provided private keys together. Used when performing key rotation. The result is a PrivateKeyHandle if
+/// either of the provided keys was.
+pub fn add_private_keys(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let private_key_a_value: Handle<JsValue> = cx.argument::<JsValue>(0)?;
+    let private_key_b_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let as_handle = handles::is_private_key_handle(&mut cx, private_key_a_value)
+        || handles::is_private_key_handle(&mut cx, private_key_b_value);
+    let augmented = handles::js_value_to_private_key(&mut cx, private_key_a_value, "privateKeyA")?
+        .augment_plus(&handles::js_value_to_private_key(
+            &mut cx,
+            private_key_b_value,
+            "privateKeyB",
+        )?);
+
+    handles::private_key_to_js_value(&mut cx, augmented, as_handle)
+}
+
+/// Subtract the second provided private key from the first provided private key. Used when performing key rotation. The
+/// result is a PrivateKeyHandle if either of the provided keys was.
+pub fn subtract_private_keys(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let private_key_a_value: Handle<JsValue> = cx.argument::<JsValue>(0)?;
+    let private_key_b_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let as_handle = handles::is_private_key_handle(&mut cx, private_key_a_value)
+        || handles::is_private_key_handle(&mut cx, private_key_b_value);
+    let augmented = handles::js_value_to_private_key(&mut cx, private_key_a_value, "privateKeyA")?
+        .augment_minus(&handles::js_value_to_private_key(
+            &mut cx,
+            private_key_b_value,
+            "privateKeyB",
+        )?);
+
+    handles::private_key_to_js_value(&mut cx, augmented, as_handle)
+}
+
+/// Separate the batch elements which converted successfully from those which didn't. Returns the converted inputs along
+/// with one slot per element which holds the conversion error, if there was one.
+fn split_batch<I, E>(batch: Vec<Result<I, E>>) -> (Vec<I>, Vec<Result<(), E>>) {
+    let mut inputs = Vec::with_capacity(batch.len());
+    let mut slots = Vec::with_capacity(batch.len());
+    for element in batch {
+        match element {
+            Ok(input) => {
+                inputs.push(input);
+                slots.push(Ok(()));
+            }
+            Err(error) => slots.push(Err(error)),
+        }
+    }
+    (inputs, slots)
+}
+
+/// Run `op` over each of the inputs, spreading the work across the rayon threadpool if `parallel` is set.
+fn map_inputs<I, O, F>(inputs: Vec<I>, parallel: bool, op: F) -> Vec<O>
+where
+    I: Send,
+    O: Send,
+    F: Fn(I) -> O + Send + Sync,
+{
+    if parallel {
+        inputs.into_par_iter().map(op).collect()
+    } else {
+        inputs.into_iter().map(op).collect()
+    }
+}
+
+/// Put the outputs produced from the inputs of `split_batch` back into their slots, so that the results line up with
+/// the original batch.
+fn merge_batch<O, E>(slots: Vec<Result<(), E>>, outputs: Vec<O>) -> Vec<Result<O, E>> {
+    let mut outputs = outputs.into_iter();
+    slots
+        .into_iter()
+        .map(|slot| slot.map(|()| outputs.next().expect("one output per converted input")))
+        .collect()
+}
+
+/// Run `op` over each batch element which converted successfully, spreading the work across the rayon threadpool if
+/// `parallel` is set. Elements which failed to convert keep their error so that the results line up with the input.
+fn run_batch<'a, I, O, F>(
+    batch: Vec<Result<I, Handle<'a, JsValue>>>,
+    parallel: bool,
+    op: F,
+) -> Vec<Result<O, Handle<'a, JsValue>>>
+where
+    I: Send,
+    O: Send,
+    F: Fn(I) -> O + Send + Sync,
+{
+    let (inputs, slots) = split_batch(batch);
+    merge_batch(slots, map_inputs(inputs, parallel, op))
+}
+
+/// The result of decrypting a single element of a `decryptMany` batch.
+enum DecryptedValue {
+    Plaintext(Box<Plaintext>),
+    SymmetricKey(DerivedSymmetricKey),
+}
+
+impl DecryptedValue {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            DecryptedValue::Plaintext(plaintext) => plaintext.bytes(),
+            DecryptedValue::SymmetricKey(symmetric_key) => symmetric_key.bytes(),
+        }
+    }
+}
+
+/// Decrypt a single element of a `decryptMany` batch, optionally deriving the symmetric key from the decrypted plaintext.
+fn decrypt_batch_element(
+    api: &Recrypt<Sha256, Ed25519, RandomBytes<Api256Rng>>,
+    encrypted_value: EncryptedValue,
+    private_key: &PrivateKey,
+    derive_symmetric_key: bool,
+) -> Result<DecryptedValue, RecryptErr> {
+    let plaintext = api.decrypt(encrypted_value, private_key)?;
+    if derive_symmetric_key {
+        Ok(DecryptedValue::SymmetricKey(
+            api.derive_symmetric_key(&plaintext),
+        ))
+    } else {
+        Ok(DecryptedValue::Plaintext(Box::new(plaintext)))
+    }
+}
+
+/// Pull the `ciphertext` Buffer and the `encryptedDeks` object out of a shared document object.
+fn js_object_to_shared_document<'a, T: Context<'a>>(
+    cx: &mut T,
+    document_obj: Handle<JsObject>,
+) -> NeonResult<(Handle<'a, JsBuffer>, Handle<'a, JsObject>)> {
+    let ciphertext_buffer = util::get_field(cx, document_obj, "document", "ciphertext", "Buffer")?;
+    let encrypted_deks = util::get_field(cx, document_obj, "document", "encryptedDeks", "object")?;
+    Ok((ciphertext_buffer, encrypted_deks))
+}
+
+/// Build a shared document object. The ciphertext is shared with the document it came from, since it never changes.
+fn shared_document_to_js_object<'a, T: Context<'a>>(
+    cx: &mut T,
+    ciphertext_buffer: Handle<JsBuffer>,
+    encrypted_deks: Handle<JsObject>,
+) -> JsResult<'a, JsObject> {
+    let document = cx.empty_object();
+    document.set(cx, "ciphertext", ciphertext_buffer)?;
+    document.set(cx, "encryptedDeks", encrypted_deks)?;
+    Ok(document)
+}
+
+/// Get the encrypted DEK for the provided recipient out of a shared document's `encryptedDeks` object. Throws an error
+/// with code `UnknownRecipient` if the recipient doesn't have one.
+fn recipient_encrypted_dek<'a, T: Context<'a>>(
+    cx: &mut T,
+    encrypted_deks: Handle<'a, JsObject>,
+    recipient_id: &str,
+) -> NeonResult<EncryptedValue> {
+    let has_recipient = util::js_object_entries(cx, encrypted_deks)?
+        .iter()
+        .any(|(existing_id, _)| existing_id == recipient_id);
+    if !has_recipient {
+        return util::throw_error(
+            cx,
+            util::ErrorKind::Error,
+            util::ErrorCode::UnknownRecipient,
+            Some("recipientId"),
+            &format!(
+                "The document has no encrypted DEK for recipient '{}'.",
+                recipient_id
+            ),
+        );
+    }
+    let encrypted_dek_obj = util::get_field(
+        cx,
+        encrypted_deks,
+        "document.encryptedDeks",
+        recipient_id,
+        "object",
+    )?;
+    util::js_object_to_encrypted_value(
+        cx,
+        encrypted_dek_obj,
+        &format!("document.encryptedDeks.{}", recipient_id),
+    )
+}
+
+/// Add a random augmentation factor to the current private key. Returns the new private key, the factor and the new
+/// public key, after checking that the new public key is the current public key augmented with the factor's public key.
+fn rotate_private_key<'a, T: Context<'a>>(
+    cx: &mut T,
+    api: &Recrypt<Sha256, Ed25519, RandomBytes<Api256Rng>>,
+    current_private_key: &PrivateKey,
+    field_path: &str,
+) -> NeonResult<(PrivateKey, PrivateKey, PublicKey)> {
+    let current_public_key =
+        util::recrypt_result_or_throw(cx, api.compute_public_key(current_private_key))?;
+    // A factor which is the negation of the current key would give a zero private key, so pick another one.
+    let (new_private_key, augmentation_factor) = loop {
+        let augmentation_factor = api.random_private_key();
+        let new_private_key = current_private_key.augment_plus(&augmentation_factor);
+        if new_private_key.bytes() != &[0u8; 32] {
+            break (new_private_key, augmentation_factor);
+        }
+    };
+    let new_public_key =
+        util::recrypt_result_or_throw(cx, api.compute_public_key(&new_private_key))?;
+    let factor_public_key =
+        util::recrypt_result_or_throw(cx, api.compute_public_key(&augmentation_factor))?;
+    let expected_public_key =
+        util::recrypt_result_or_throw(cx, current_public_key.augment(&factor_public_key))?;
+    if new_public_key != expected_public_key {
+        return util::throw_error(
+            cx,
+            util::ErrorKind::Error,
+            util::ErrorCode::PublicKeyMismatch,
+            Some(field_path),
+            "The rotated public key doesn't match the current public key augmented with the augmentation factor.",
+        );
+    }
+    Ok((new_private_key, augmentation_factor, new_public_key))
+}
+
+/// The parsed arguments of `api256_derive_key_pair_from_passphrase`.
+struct PassphraseKeyPairArgs {
+    api: Arc<Recrypt<Sha256, Ed25519, RandomBytes<Api256Rng>>>,
+    passphrase: Zeroizing<Vec<u8>>,
+    salt: Vec<u8>,
+    params: passphrase::KdfParams,
+    as_handle: bool,
+}
+
+/// Parse the `(passphrase, salt, kdfParams?, options?)` arguments of `api256_derive_key_pair_from_passphrase`.
+fn passphrase_key_pair_args(cx: &mut FunctionContext) -> NeonResult<PassphraseKeyPairArgs> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let passphrase_value: Handle<JsValue> = cx.argument::<JsValue>(1)?;
+    let salt_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(2)?;
+    let params_arg: Option<Handle<JsValue>> = cx.argument_opt(3);
+    let options: Option<Handle<JsValue>> = cx.argument_opt(4);
+
+    Ok(PassphraseKeyPairArgs {
+        api: recrypt_api_256.api.clone(),
+        passphrase: passphrase::js_value_to_passphrase(cx, passphrase_value, "passphrase")?,
+        salt: passphrase::buffer_to_salt(cx, salt_buffer, "salt")?,
+        params: passphrase::js_value_to_kdf_params(cx, params_arg, "kdfParams")?,
+        as_handle: util::get_bool_option(cx, options, "handle")?,
+    })
+}
+
+/// Stretch the passphrase into a private key and compute its public key. The salt and parameters must have been
+/// validated.
+fn derive_key_pair_from_passphrase(
+    api: &Recrypt<Sha256, Ed25519, RandomBytes<Api256Rng>>,
+    passphrase: &[u8],
+    salt: &[u8],
+    params: passphrase::KdfParams,
+) -> Result<(PrivateKey, PublicKey), RecryptErr> {
+    let priv_key = PrivateKey::new(
+        *params
+            .derive_key(passphrase, salt)
+            .expect("KDF parameters and salt were validated"),
+    );
+    let pub_key = api.compute_public_key(&priv_key)?;
+    Ok((priv_key, pub_key))
+}
+
+/// The parsed arguments of `api256_import_private_key`.
+struct ImportPrivateKeyArgs {
+    api: Arc<Recrypt<Sha256, Ed25519, RandomBytes<Api256Rng>>>,
+    blob: Vec<u8>,
+    passphrase: Zeroizing<Vec<u8>>,
+    as_handle: bool,
+}
+
+/// Parse the `(blob, passphrase, options?)` arguments of `api256_import_private_key`.
+fn import_private_key_args(cx: &mut FunctionContext) -> NeonResult<ImportPrivateKeyArgs> {
+    let recrypt_api_256 = cx.argument::<JsBox<RecryptApi256>>(0)?;
+    let blob_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let passphrase_value: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(3);
+
+    Ok(ImportPrivateKeyArgs {
+        api: recrypt_api_256.api.clone(),
+        passphrase: passphrase::js_value_to_passphrase(cx, passphrase_value, "passphrase")?,
+        as_handle: util::get_bool_option(cx, options, "handle")?,
+        blob: util::buffer_to_variable_bytes(cx, blob_buffer),
+    })
+}
+
+/// Check the key decrypted from a backup against the public key stored with it and build the `{keyType, privateKey,
+/// publicKey}` object returned from an import.
+fn imported_key_to_js_object<'a, T: Context<'a>>(
+    cx: &mut T,
+    api: &Recrypt<Sha256, Ed25519, RandomBytes<Api256Rng>>,
+    imported: passphrase::ImportedKey,
+    as_handle: bool,
+) -> JsResult<'a, JsObject> {
+    let mismatch =
+        "The decrypted private key in 'blob' doesn't match the public key stored with it.";
+    let (result, key_type) = match imported.key_type {
+        passphrase::BackupKeyType::Recrypt256 => {
+            let mut private_key_bytes = Zeroizing::new([0u8; 32]);
+            private_key_bytes.copy_from_slice(&imported.private_key);
+            let private_key = PrivateKey::new(*private_key_bytes);
+            let computed_public_key =
+                util::recrypt_result_or_throw(cx, api.compute_public_key(&private_key))?;
+            let (x, y) = computed_public_key.bytes_x_y();
+            if [&x[..], &y[..]].concat() != imported.public_key {
+                return util::throw_error(
+                    cx,
+                    util::ErrorKind::Error,
+                    util::ErrorCode::InvalidEncoding,
+                    Some("blob"),
+                    mismatch,
+                );
+            }
+            (
+                key_pair_to_js_object(cx, private_key, &computed_public_key, as_handle)?,
+                "recrypt",
+            )
+        }
+        passphrase::BackupKeyType::Ed25519 => {
+            let mut signing_key_bytes = Zeroizing::new([0u8; 64]);
+            signing_key_bytes.copy_from_slice(&imported.private_key);
+            let signing_key_pair = match SigningKeypair::from_bytes(&signing_key_bytes) {
+                Ok(signing_key_pair)
+                    if signing_key_pair.public_key().bytes()[..] == imported.public_key[..] =>
+                {
+                    signing_key_pair
+                }
+                _ => {
+                    return util::throw_error(
+                        cx,
+                        util::ErrorKind::Error,
+                        util::ErrorCode::InvalidEncoding,
+                        Some("blob"),
+                        mismatch,
+                    );
+                }
+            };
+            (
+                handles::signing_key_pair_to_js_object(cx, signing_key_pair, as_handle)?,
+                "ed25519",
+            )
+        }
+    };
+    let key_type = cx.string(key_type);
+    result.set(cx, "keyType", key_type)?;
+    Ok(result)
+}
+
+/// Build the `{privateKey, publicKey}` object returned from every function which produces a key pair. If `as_handle` is
+/// set the private key is returned as a PrivateKeyHandle instead of a Buffer.
+fn key_pair_to_js_object<'a, T: Context<'a>>(
+    cx: &mut T,
+    private_key: PrivateKey,
+    public_key: &PublicKey,
+    as_handle: bool,
+) -> JsResult<'a, JsObject> {
+    let key_pair = cx.empty_object();
+    let private_key_value = handles::private_key_to_js_value(cx, private_key, as_handle)?;
+    let public_key_obj = util::public_key_to_js_object(cx, public_key)?;
+    key_pair.set(cx, "privateKey", private_key_value)?;
+    key_pair.set(cx, "publicKey", public_key_obj)?;
+    Ok(key_pair)
+}
+
+/// Parse the optional `expectedPublicKey` argument which key recovery functions check the recovered key against.
+/// `undefined` and `null` are treated as not provided.
+fn optional_expected_public_key<'a, T: Context<'a>>(
+    cx: &mut T,
+    value: Option<Handle<'a, JsValue>>,
+) -> NeonResult<Option<PublicKey>> {
+    match util::downcast_optional_argument::<JsObject, _>(cx, value, "expectedPublicKey", "object")?
+    {
+        Some(public_key_obj) => Ok(Some(util::js_object_to_public_key(
+            cx,
+            public_key_obj,
+            "expectedPublicKey",
+        )?)),
+        None => Ok(None),
+    }
+}
 
-    util::bytes_to_buffer(&mut cx, &augmented.to_bytes())
+/// Build the `{privateKey, augmentationFactor, publicKey}` object returned from a key rotation.
+fn rotation_to_js_object<'a, T: Context<'a>>(
+    cx: &mut T,
+    (new_private_key, augmentation_factor, new_public_key): (PrivateKey, PrivateKey, PublicKey),
+    as_handle: bool,
+) -> JsResult<'a, JsObject> {
+    let rotation = cx.empty_object();
+    let new_private_key_value = handles::private_key_to_js_value(cx, new_private_key, as_handle)?;
+    let augmentation_factor_value =
+        handles::private_key_to_js_value(cx, augmentation_factor, as_handle)?;
+    let new_public_key_obj = util::public_key_to_js_object(cx, &new_public_key)?;
+
+    rotation.set(cx, "privateKey", new_private_key_value)?;
+    rotation.set(cx, "augmentationFactor", augmentation_factor_value)?;
+    rotation.set(cx, "publicKey", new_public_key_obj)?;
+    Ok(rotation)
 }
@@ -0,0 +1,324 @@
+use crate::compression;
+use crate::handles;
+use crate::util;
+use crate::util::api480 as util480;
+use neon::{prelude::*, types::JsBuffer};
+use recrypt::api_480::{
+    CryptoOps, DefaultRng, Ed25519, Ed25519Ops, Hashable, KeyGenOps, RandomBytes, Recrypt480,
+    SchnorrOps, Sha256,
+};
+
+pub struct RecryptApi480 {
+    api: Recrypt480<Sha256, Ed25519, RandomBytes<DefaultRng>>,
+}
+
+impl Finalize for RecryptApi480 {}
+
+// These mirror the functions in `api256.rs`, but operate on the 480-bit curve. The Ed25519 signing functions don't
+// depend on the curve, so only key pair generation lives here and the rest are shared with the 256-bit API.
+pub fn api480_create_recrypt_api_480(mut cx: FunctionContext) -> JsResult<JsBox<RecryptApi480>> {
+    Ok(cx.boxed(RecryptApi480 {
+        api: Recrypt480::new(),
+    }))
+}
+
+pub fn api480_generate_key_pair(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+
+    let (priv_key, pub_key) =
+        util::recrypt_result_or_throw(&mut cx, recrypt_api_480.api.generate_key_pair())?;
+    let key_pair = cx.empty_object();
+    let priv_key_buffer = util::bytes_to_buffer(&mut cx, priv_key.bytes())?;
+    let public_key_obj = util480::public_key_to_js_object(&mut cx, &pub_key)?;
+
+    key_pair.set(&mut cx, "privateKey", priv_key_buffer)?;
+    key_pair.set(&mut cx, "publicKey", public_key_obj)?;
+
+    Ok(key_pair)
+}
+
+/// Generate a new Ed25519 signing key pair. If the `handle` option is set, the private key is returned as a
+/// SigningKeyPairHandle which keeps it in native memory instead of a Buffer.
+pub fn api480_generate_ed25519_key_pair(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let options: Option<Handle<JsValue>> = cx.argument_opt(1);
+    let as_handle = util::get_bool_option(&mut cx, options, "handle")?;
+
+    let signing_key_pair = recrypt_api_480.api.generate_ed25519_key_pair();
+
+    handles::signing_key_pair_to_js_object(&mut cx, signing_key_pair, as_handle)
+}
+
+pub fn api480_generate_plaintext(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+
+    let plaintext = recrypt_api_480.api.gen_plaintext();
+
+    util::bytes_to_buffer(&mut cx, plaintext.bytes())
+}
+
+pub fn api480_generate_transform_key(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let from_private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+    let to_public_key =
+        util480::js_object_to_public_key(&mut cx, to_public_key_obj, "toPublicKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+    let from_private_key =
+        util480::buffer_to_private_key(&mut cx, from_private_key_buffer, "fromPrivateKey")?;
+
+    let transform_key = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_480.api.generate_transform_key(
+            &from_private_key,
+            &to_public_key,
+            &signing_key_pair,
+        ),
+    )?;
+
+    util480::transform_key_to_js_object(&mut cx, transform_key)
+}
+
+pub fn api480_compute_public_key(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+
+    let private_key = util480::buffer_to_private_key(&mut cx, private_key_buffer, "privateKey")?;
+
+    let derived_public_key = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_480.api.compute_public_key(&private_key),
+    )?;
+
+    util480::public_key_to_js_object(&mut cx, &derived_public_key)
+}
+
+pub fn api480_derive_symmetric_key(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+
+    let plaintext = util480::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+
+    let decrypted_symmetric_key = recrypt_api_480.api.derive_symmetric_key(&plaintext);
+
+    util::bytes_to_buffer(&mut cx, decrypted_symmetric_key.bytes())
+}
+
+pub fn api480_encrypt(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let plaintext_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let to_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+    let public_key = util480::js_object_to_public_key(&mut cx, to_public_key_obj, "toPublicKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+    let plaintext = util480::buffer_to_plaintext(&mut cx, plaintext_buffer, "plaintext")?;
+
+    let encrypted_value = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_480
+            .api
+            .encrypt(&plaintext, &public_key, &signing_key_pair),
+    )?;
+
+    util480::encrypted_value_to_js_object(&mut cx, encrypted_value)
+}
+
+pub fn api480_transform(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let private_signing_key_value: Handle<JsValue> = cx.argument::<JsValue>(3)?;
+
+    let encrypted_value =
+        util480::js_object_to_encrypted_value(&mut cx, encrypted_value_obj, "encryptedValue")?;
+    let transform_key =
+        util480::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
+    let signing_key_pair = handles::js_value_to_signing_key_pair(
+        &mut cx,
+        private_signing_key_value,
+        "privateSigningKey",
+    )?;
+
+    let transformed_encrypted_value = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_480
+            .api
+            .transform(encrypted_value, transform_key, &signing_key_pair),
+    )?;
+
+    util480::encrypted_value_to_js_object(&mut cx, transformed_encrypted_value)
+}
+
+pub fn api480_decrypt(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let encrypted_value_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(2)?;
+
+    let encrypted_value =
+        util480::js_object_to_encrypted_value(&mut cx, encrypted_value_obj, "encryptedValue")?;
+    let private_key = util480::buffer_to_private_key(&mut cx, private_key_buffer, "privateKey")?;
+
+    let decrypted_value = util::recrypt_result_or_throw(
+        &mut cx,
+        recrypt_api_480.api.decrypt(encrypted_value, &private_key),
+    )?;
+
+    util::bytes_to_buffer(&mut cx, decrypted_value.bytes())
+}
+
+pub fn api480_schnorr_sign(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(2)?;
+    let message_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
+
+    let public_key = util480::js_object_to_public_key(&mut cx, public_key_obj, "publicKey")?;
+    let private_key = util480::buffer_to_private_key(&mut cx, private_key_buffer, "privateKey")?;
+
+    let signature = recrypt_api_480.api.schnorr_sign(
+        &private_key,
+        &public_key,
+        &util::buffer_to_variable_bytes(&cx, message_buffer),
+    );
+
+    util::bytes_to_buffer(&mut cx, signature.bytes())
+}
+
+pub fn api480_schnorr_verify(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let recrypt_api_480 = cx.argument::<JsBox<RecryptApi480>>(0)?;
+    let public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+    //The augmented private key is an optional argument to take in a generic JsValue
+    let augmented_private_key_buffer: Handle<JsValue> = cx.argument::<JsValue>(2)?;
+    let message_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(3)?;
+    let signature_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(4)?;
+
+    let public_key = util480::js_object_to_public_key(&mut cx, public_key_obj, "publicKey")?;
+    let signature = util480::buffer_to_schnorr_signature(&mut cx, signature_buffer, "signature")?;
+
+    let augmented_private_key = {
+        //Ignore both null or undefined as values are passed for augmented private key
+        if augmented_private_key_buffer.is_a::<JsUndefined, _>(&mut cx)
+            || augmented_private_key_buffer.is_a::<JsNull, _>(&mut cx)
+        {
+            None
+        } else {
+            let casted_private_key_buffer = util::downcast_field::<JsBuffer, _>(
+                &mut cx,
+                augmented_private_key_buffer,
+                "augmentedPrivateKey",
+                "Buffer",
+            )?;
+            Some(util480::buffer_to_private_key(
+                &mut cx,
+                casted_private_key_buffer,
+                "augmentedPrivateKey",
+            )?)
+        }
+    };
+
+    let verified = recrypt_api_480.api.schnorr_verify(
+        &public_key,
+        augmented_private_key.as_ref(),
+        &util::buffer_to_variable_bytes(&cx, message_buffer),
+        signature,
+    );
+
+    Ok(cx.boolean(verified))
+}
+
+/// Augment the provided 480-bit transform key with the provided private key. Returns an augmented TransformKey object.
+pub fn augment_transform_key_480(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let transform_key =
+        util480::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
+    let private_key = util480::buffer_to_private_key(&mut cx, private_key_buffer, "privateKey")?;
+
+    let augmented_transform_key =
+        util::recrypt_result_or_throw(&mut cx, transform_key.augment(&private_key))?;
+
+    util480::transform_key_to_js_object(&mut cx, augmented_transform_key)
+}
+
+/// Augment the provided 480-bit public key with the other provided public key. Returns a new augmented PublicKey object.
+pub fn augment_public_key_480(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let current_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let other_public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(1)?;
+
+    let current_public_key =
+        util480::js_object_to_public_key(&mut cx, current_public_key_obj, "publicKey")?;
+    let other_public_key =
+        util480::js_object_to_public_key(&mut cx, other_public_key_obj, "otherPublicKey")?;
+
+    let augmented_public_key =
+        util::recrypt_result_or_throw(&mut cx, current_public_key.augment(&other_public_key))?;
+
+    util480::public_key_to_js_object(&mut cx, &augmented_public_key)
+}
+
+/// Hash the provided 480-bit transform key into a buffer of bytes. The various transform key object fields are
+/// concatenated in a specific order in order for transform keys to be signed over.
+pub fn transform_key_to_bytes_480(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let transform_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let transform_key =
+        util480::js_object_to_transform_key(&mut cx, transform_key_obj, "transformKey")?;
+
+    util::bytes_to_buffer(&mut cx, &transform_key.to_bytes())
+}
+
+/// Compress the provided 480-bit public key into 61 bytes: a prefix byte which records whether y is odd, followed by x.
+pub fn compress_public_key_480(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let public_key_obj: Handle<JsObject> = cx.argument::<JsObject>(0)?;
+    let public_key = util480::js_object_to_public_key(&mut cx, public_key_obj, "publicKey")?;
+
+    util::bytes_to_buffer(&mut cx, &compression::compress_public_key_480(&public_key))
+}
+
+/// Decompress the provided 61 byte compressed public key into a 480-bit PublicKey object. Throws if the bytes aren't a
+/// valid point on the curve.
+pub fn decompress_public_key_480(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let compressed_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let public_key = util480::js_object_to_public_key(
+        &mut cx,
+        compressed_buffer.upcast(),
+        "compressedPublicKey",
+    )?;
+
+    util480::public_key_to_js_object(&mut cx, &public_key)
+}
+
+/// Add the two provided 480-bit private keys together. Used when performing key rotation.
+pub fn add_private_keys_480(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let priv_key_a: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let priv_key_b: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let augmented =
+        util480::buffer_to_private_key(&mut cx, priv_key_a, "privateKeyA")?.augment_plus(
+            &util480::buffer_to_private_key(&mut cx, priv_key_b, "privateKeyB")?,
+        );
+
+    util::bytes_to_buffer(&mut cx, &augmented.to_bytes())
+}
+
+/// Subtract the second provided 480-bit private key from the first provided private key. Used when performing key
+/// rotation
+pub fn subtract_private_keys_480(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let priv_key_a: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let priv_key_b: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let augmented =
+        util480::buffer_to_private_key(&mut cx, priv_key_a, "privateKeyA")?.augment_minus(
+            &util480::buffer_to_private_key(&mut cx, priv_key_b, "privateKeyB")?,
+        );
+
+    util::bytes_to_buffer(&mut cx, &augmented.to_bytes())
+}
@@ -0,0 +1,81 @@
+use gridiron::fp_256::Fp256;
+use gridiron::fp_480::Fp480;
+use num_traits::Pow;
+
+// Compressed public keys use the SEC 1 layout: a prefix byte of 0x02 if y is even or 0x03 if y is odd, followed by x.
+// Both curves are y^2 = x^3 + 3 over a prime field where p = 3 mod 4, so y can be recovered as (x^3 + 3)^((p + 1) / 4).
+
+const EVEN_Y_PREFIX: u8 = 0x02;
+const ODD_Y_PREFIX: u8 = 0x03;
+
+/// Generate the functions to compress and decompress public keys on one of the curves. `$sqrt_exponent` is the big
+/// endian encoding of (p + 1) / 4 for the curve's field.
+macro_rules! point_compression {
+    ($compress: ident, $decompress: ident, $field: ident, $public_key: ty, $n: expr, $sqrt_exponent: expr) => {
+        /// Compress the provided public key into its prefix byte followed by its x coordinate.
+        pub fn $compress(public_key: &$public_key) -> [u8; $n + 1] {
+            let (x, y) = public_key.bytes_x_y();
+            let mut compressed = [0u8; $n + 1];
+            compressed[0] = if y[$n - 1] & 1 == 0 {
+                EVEN_Y_PREFIX
+            } else {
+                ODD_Y_PREFIX
+            };
+            compressed[1..].copy_from_slice(x);
+            compressed
+        }
+
+        /// Recover the public key from its compressed form. Fails if the prefix is unknown, if x isn't a canonical
+        /// field element or if there is no point on the curve with that x coordinate.
+        pub fn $decompress(compressed: &[u8; $n + 1]) -> Result<$public_key, String> {
+            let odd = match compressed[0] {
+                EVEN_Y_PREFIX => false,
+                ODD_Y_PREFIX => true,
+                prefix => return Err(format!("Unknown compressed point prefix {}.", prefix)),
+            };
+            let mut x_bytes = [0u8; $n];
+            x_bytes.copy_from_slice(&compressed[1..]);
+            let x = $field::from(x_bytes);
+            if x.to_bytes_array() != x_bytes {
+                return Err("The x coordinate is not less than the field modulus.".to_string());
+            }
+            let y_squared = x * x * x + $field::from(3u8);
+            let mut y = y_squared.pow($field::from($sqrt_exponent));
+            if y * y != y_squared {
+                return Err("The x coordinate is not on the curve.".to_string());
+            }
+            let y_is_odd = y.to_bytes_array()[$n - 1] & 1 == 1;
+            if y_is_odd != odd {
+                y = -y;
+            }
+            <$public_key>::new((x_bytes, y.to_bytes_array())).map_err(|e| e.to_string())
+        }
+    };
+}
+
+point_compression!(
+    compress_public_key,
+    decompress_public_key,
+    Fp256,
+    recrypt::api::PublicKey,
+    32,
+    [
+        0x23, 0xed, 0x40, 0x78, 0xd2, 0xa8, 0xe1, 0xfe, 0x6a, 0x9b, 0xfb, 0x2e, 0x18, 0x61, 0x37,
+        0x08, 0x7b, 0x96, 0xe2, 0x34, 0x48, 0x2d, 0x6d, 0x67, 0x86, 0x17, 0x2b, 0x1b, 0x17, 0x82,
+        0x25, 0x9a
+    ]
+);
+
+point_compression!(
+    compress_public_key_480,
+    decompress_public_key_480,
+    Fp480,
+    recrypt::api_480::PublicKey,
+    60,
+    [
+        0x3f, 0xff, 0x19, 0x99, 0x03, 0x89, 0x27, 0x67, 0xb1, 0xd6, 0xb5, 0x4a, 0x42, 0xe0, 0x6a,
+        0x17, 0x50, 0x55, 0xe5, 0xee, 0x4c, 0x49, 0x63, 0x68, 0x35, 0xe2, 0xd6, 0x28, 0x87, 0x10,
+        0xd7, 0x37, 0x6c, 0x0b, 0x82, 0xb7, 0x58, 0xd6, 0x80, 0xdc, 0xdc, 0x74, 0x7a, 0x69, 0x02,
+        0x97, 0xb0, 0x75, 0xbb, 0x58, 0xde, 0xf4, 0xda, 0x55, 0x4c, 0x1a, 0x0f, 0xba, 0x59, 0x26
+    ]
+);
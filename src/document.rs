@@ -0,0 +1,139 @@
+use crate::aes;
+use crate::serialization::{ByteReader, SerializationErr};
+use crate::util::{self, ErrorCode, ErrorKind};
+use neon::prelude::*;
+use recrypt::api::DerivedSymmetricKey;
+use serde::{Deserialize, Serialize};
+
+// The encrypted document format shared by the other IronCore SDKs (ironoxide, ironnode and ironweb). The AES-256-GCM
+// encrypted data is prefixed with a header which identifies the document, so a reader can find the key it needs before
+// decrypting. Version 2:
+//
+//   offset  size  field
+//   0       1     version (2)
+//   1       2     length of the JSON header
+//   3       n     JSON header, e.g. {"_did_":"documentId","_sid_":1}
+//   3 + n   12    AES-GCM nonce
+//   15 + n  ...   encrypted data followed by the 16 byte GCM tag
+//
+// The header is not covered by the GCM tag, so a document can be decrypted after its header has been modified. Callers
+// should check the document id against the one they expected to read. Version 3 documents, which start with an "IRON"
+// magic and use a protobuf header, aren't supported and are rejected as an unsupported version.
+
+pub const DOCUMENT_VERSION: u8 = 2;
+// Segment ids are JS numbers, so only integers up to 2^53 - 1 can be represented exactly.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// The metadata stored in a document's header. `segment_id` is the id of the segment which owns the document.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentHeader {
+    #[serde(rename = "_did_")]
+    pub document_id: String,
+    #[serde(rename = "_sid_")]
+    pub segment_id: u64,
+}
+
+/// Serialize the header into its JSON form. Fails if it's too long for the 2 byte length.
+fn header_to_json(header: &DocumentHeader) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(header).expect("DocumentHeader always serializes");
+    if json.len() > u16::MAX as usize {
+        return Err(format!(
+            "The document header is {} bytes, but can be at most {} bytes.",
+            json.len(),
+            u16::MAX
+        ));
+    }
+    Ok(json)
+}
+
+/// Encrypt the data with the symmetric key and prefix it with the header. The header must have been validated by
+/// `js_object_to_document_header`.
+pub fn encrypt_document(
+    header: &DocumentHeader,
+    key: &DerivedSymmetricKey,
+    data: &[u8],
+) -> Vec<u8> {
+    let header_json = header_to_json(header).expect("header length was validated");
+    let encrypted = aes::encrypt(key.bytes(), data, &[]);
+    let mut document = Vec::with_capacity(1 + 2 + header_json.len() + encrypted.len());
+    document.push(DOCUMENT_VERSION);
+    document.extend_from_slice(&(header_json.len() as u16).to_be_bytes());
+    document.extend_from_slice(&header_json);
+    document.extend_from_slice(&encrypted);
+    document
+}
+
+/// Parse the header off the front of an encrypted document. Returns the header and the AES-GCM encrypted data which
+/// follows it, which can be decrypted with `aes::decrypt`.
+pub fn parse_document<'a>(
+    bytes: &'a [u8],
+    field_path: &'a str,
+) -> Result<(DocumentHeader, &'a [u8]), SerializationErr> {
+    let mut reader = ByteReader::new(bytes, field_path);
+    reader.read_version(DOCUMENT_VERSION)?;
+    let header_len = reader.read_u16("the header length")?;
+    let header_json = reader.read_slice(header_len as usize, "the header")?;
+    let header = serde_json::from_slice(header_json).map_err(|e| {
+        reader.invalid(
+            ErrorCode::InvalidEncoding,
+            format!(
+                "Provided bytes for '{}' have an invalid document header. {}",
+                field_path, e
+            ),
+        )
+    })?;
+    Ok((header, reader.read_rest()))
+}
+
+/// Convert a `{documentId, segmentId}` JS object into a DocumentHeader. The segment id must be a non-negative integer
+/// and the document id must be short enough for the header length to fit in 2 bytes.
+pub fn js_object_to_document_header<'a, T: Context<'a>>(
+    cx: &mut T,
+    object: Handle<JsObject>,
+    field_path: &str,
+) -> NeonResult<DocumentHeader> {
+    let document_id: Handle<JsString> =
+        util::get_field(cx, object, field_path, "documentId", "string")?;
+    let segment_id: Handle<JsNumber> =
+        util::get_field(cx, object, field_path, "segmentId", "number")?;
+    let segment_id = segment_id.value(cx);
+    if segment_id < 0.0 || segment_id.fract() != 0.0 || segment_id > MAX_SAFE_INTEGER {
+        let full_path = format!("{}.segmentId", field_path);
+        return util::throw_error(
+            cx,
+            ErrorKind::TypeError,
+            ErrorCode::InvalidFieldType,
+            Some(&full_path),
+            &format!(
+                "Provided value for '{}' must be a non-negative integer.",
+                full_path
+            ),
+        );
+    }
+    let header = DocumentHeader {
+        document_id: document_id.value(cx),
+        segment_id: segment_id as u64,
+    };
+    if let Err(message) = header_to_json(&header) {
+        return util::throw_error(
+            cx,
+            ErrorKind::RangeError,
+            ErrorCode::InputWrongSize,
+            Some(&format!("{}.documentId", field_path)),
+            &message,
+        );
+    }
+    Ok(header)
+}
+
+pub fn document_header_to_js_object<'a, T: Context<'a>>(
+    cx: &mut T,
+    header: DocumentHeader,
+) -> JsResult<'a, JsObject> {
+    let object = cx.empty_object();
+    let document_id = cx.string(header.document_id);
+    let segment_id = cx.number(header.segment_id as f64);
+    object.set(cx, "documentId", document_id)?;
+    object.set(cx, "segmentId", segment_id)?;
+    Ok(object)
+}
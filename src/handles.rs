@@ -0,0 +1,125 @@
+use crate::util;
+use neon::{prelude::*, types::JsBuffer};
+use recrypt::api::{PrivateKey, SigningKeypair};
+
+// Native handles for secret keys. A handle is an opaque JS object which holds the key in Rust memory, so the key bytes
+// never end up on the V8 heap unless they're explicitly exported.
+
+/// A 256-bit private key held natively.
+pub struct PrivateKeyHandle(pub PrivateKey);
+
+// Dropping a recrypt PrivateKey clears its bytes, so the key is zeroized when the handle is garbage collected.
+impl Finalize for PrivateKeyHandle {}
+
+/// Returns true if the provided value is a PrivateKeyHandle rather than a Buffer.
+pub fn is_private_key_handle<'a, T: Context<'a>>(cx: &mut T, value: Handle<JsValue>) -> bool {
+    value.is_a::<JsBox<PrivateKeyHandle>, _>(cx)
+}
+
+/// Convert a private key argument, which may be either a Buffer or a PrivateKeyHandle, into a PrivateKey.
+pub fn js_value_to_private_key<'a, T: Context<'a>>(
+    cx: &mut T,
+    value: Handle<'a, JsValue>,
+    field_path: &str,
+) -> NeonResult<PrivateKey> {
+    if let Ok(handle) = value.downcast::<JsBox<PrivateKeyHandle>, _>(cx) {
+        return Ok(handle.0.clone());
+    }
+    let buffer: Handle<JsBuffer> =
+        util::downcast_field(cx, value, field_path, "Buffer or PrivateKeyHandle")?;
+    util::buffer_to_private_key(cx, buffer, field_path)
+}
+
+/// Return the provided private key to JS as a handle if `as_handle` is set and as a Buffer otherwise.
+pub fn private_key_to_js_value<'a, T: Context<'a>>(
+    cx: &mut T,
+    private_key: PrivateKey,
+    as_handle: bool,
+) -> JsResult<'a, JsValue> {
+    if as_handle {
+        Ok(cx.boxed(PrivateKeyHandle(private_key)).upcast())
+    } else {
+        Ok(util::bytes_to_buffer(cx, private_key.bytes())?.upcast())
+    }
+}
+
+/// Explicitly export the bytes of the private key held by a PrivateKeyHandle.
+pub fn private_key_handle_to_bytes(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = cx.argument::<JsBox<PrivateKeyHandle>>(0)?;
+
+    util::bytes_to_buffer(&mut cx, handle.0.bytes())
+}
+
+/// Import the provided private key bytes into a new PrivateKeyHandle. The caller is responsible for clearing the Buffer.
+pub fn private_key_handle_from_bytes(mut cx: FunctionContext) -> JsResult<JsBox<PrivateKeyHandle>> {
+    let private_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let private_key = util::buffer_to_private_key(&mut cx, private_key_buffer, "privateKey")?;
+
+    Ok(cx.boxed(PrivateKeyHandle(private_key)))
+}
+
+/// An Ed25519 signing key pair held natively. Signing keys aren't tied to a curve, so these handles work with both APIs.
+pub struct SigningKeyPairHandle(pub SigningKeypair);
+
+// Dropping a recrypt SigningKeypair clears its bytes, so the key is zeroized when the handle is garbage collected.
+impl Finalize for SigningKeyPairHandle {}
+
+/// Returns true if the provided value is a SigningKeyPairHandle.
+pub fn is_signing_key_pair_handle<'a, T: Context<'a>>(cx: &mut T, value: Handle<JsValue>) -> bool {
+    value.is_a::<JsBox<SigningKeyPairHandle>, _>(cx)
+}
+
+/// Convert a private signing key argument, which may be either a 64 byte Buffer or a SigningKeyPairHandle, into a
+/// SigningKeypair. Handles were validated when they were created, so they aren't parsed again.
+pub fn js_value_to_signing_key_pair<'a, T: Context<'a>>(
+    cx: &mut T,
+    value: Handle<'a, JsValue>,
+    field_path: &str,
+) -> NeonResult<SigningKeypair> {
+    if let Ok(handle) = value.downcast::<JsBox<SigningKeyPairHandle>, _>(cx) {
+        return Ok(handle.0.clone());
+    }
+    let buffer: Handle<JsBuffer> =
+        util::downcast_field(cx, value, field_path, "Buffer or SigningKeyPairHandle")?;
+    util::buffer_to_signing_key_pair(cx, buffer, field_path)
+}
+
+/// Return the provided signing key pair to JS as a `{privateKey, publicKey}` object. The private key is a handle if
+/// `as_handle` is set and a Buffer otherwise.
+pub fn signing_key_pair_to_js_object<'a, T: Context<'a>>(
+    cx: &mut T,
+    signing_key_pair: SigningKeypair,
+    as_handle: bool,
+) -> JsResult<'a, JsObject> {
+    let signing_key_pair_obj: Handle<JsObject> = cx.empty_object();
+    let pub_key_buffer = util::bytes_to_buffer(cx, signing_key_pair.public_key().bytes())?;
+    let priv_key_value: Handle<JsValue> = if as_handle {
+        cx.boxed(SigningKeyPairHandle(signing_key_pair)).upcast()
+    } else {
+        util::bytes_to_buffer(cx, signing_key_pair.bytes())?.upcast()
+    };
+
+    signing_key_pair_obj.set(cx, "privateKey", priv_key_value)?;
+    signing_key_pair_obj.set(cx, "publicKey", pub_key_buffer)?;
+
+    Ok(signing_key_pair_obj)
+}
+
+/// Explicitly export the 64 bytes of the signing key pair held by a SigningKeyPairHandle.
+pub fn signing_key_pair_handle_to_bytes(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = cx.argument::<JsBox<SigningKeyPairHandle>>(0)?;
+
+    util::bytes_to_buffer(&mut cx, handle.0.bytes())
+}
+
+/// Validate the provided 64 byte private signing key once and import it into a new SigningKeyPairHandle. The caller is
+/// responsible for clearing the Buffer.
+pub fn signing_key_pair_handle_from_bytes(
+    mut cx: FunctionContext,
+) -> JsResult<JsBox<SigningKeyPairHandle>> {
+    let private_signing_key_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(0)?;
+    let signing_key_pair =
+        util::buffer_to_signing_key_pair(&mut cx, private_signing_key_buffer, "privateSigningKey")?;
+
+    Ok(cx.boxed(SigningKeyPairHandle(signing_key_pair)))
+}
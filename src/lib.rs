@@ -1,25 +1,102 @@
 use neon::prelude::{ModuleContext, NeonResult};
 
+mod aes;
 mod api256;
+mod api480;
+mod compression;
+mod document;
+mod handles;
+mod mnemonic;
+mod passphrase;
+mod serialization;
+mod shamir;
+mod stream;
 mod util;
+mod wordlist;
 
 #[neon::main]
 fn main(mut cx: ModuleContext) -> NeonResult<()> {
     // top level functions
     cx.export_function("augmentTransformKey256", api256::augment_transform_key_256)?;
+    cx.export_function(
+        "applyRotationToTransformKeys",
+        api256::apply_rotation_to_transform_keys,
+    )?;
     cx.export_function("augmentPublicKey256", api256::augment_public_key_256)?;
     cx.export_function("transformKeyToBytes256", api256::transform_key_to_bytes_256)?;
+    cx.export_function("compressPublicKey", api256::compress_public_key)?;
+    cx.export_function("decompressPublicKey", api256::decompress_public_key)?;
+    cx.export_function("encryptedValueToBytes", api256::encrypted_value_to_bytes)?;
+    cx.export_function(
+        "encryptedValueFromBytes",
+        api256::encrypted_value_from_bytes,
+    )?;
+    cx.export_function("transformKeyToBytes", api256::transform_key_to_bytes)?;
+    cx.export_function("transformKeyFromBytes", api256::transform_key_from_bytes)?;
+    cx.export_function("parseDocumentHeader", api256::parse_document_header)?;
+    cx.export_function(
+        "privateKeyHandleToBytes",
+        handles::private_key_handle_to_bytes,
+    )?;
+    cx.export_function(
+        "privateKeyHandleFromBytes",
+        handles::private_key_handle_from_bytes,
+    )?;
+    cx.export_function(
+        "signingKeyPairHandleToBytes",
+        handles::signing_key_pair_handle_to_bytes,
+    )?;
+    cx.export_function(
+        "signingKeyPairHandleFromBytes",
+        handles::signing_key_pair_handle_from_bytes,
+    )?;
+    cx.export_function("privateKeyToMnemonic", mnemonic::private_key_to_mnemonic)?;
     cx.export_function("addPrivateKeys", api256::add_private_keys)?;
     cx.export_function("subtractPrivateKeys", api256::subtract_private_keys)?;
+    cx.export_function("augmentTransformKey480", api480::augment_transform_key_480)?;
+    cx.export_function("augmentPublicKey480", api480::augment_public_key_480)?;
+    cx.export_function("transformKeyToBytes480", api480::transform_key_to_bytes_480)?;
+    cx.export_function("compressPublicKey480", api480::compress_public_key_480)?;
+    cx.export_function("decompressPublicKey480", api480::decompress_public_key_480)?;
+    cx.export_function("addPrivateKeys480", api480::add_private_keys_480)?;
+    cx.export_function("subtractPrivateKeys480", api480::subtract_private_keys_480)?;
     // Api256 member functions
     cx.export_function("createApi256", api256::api256_create_recrypt_api_256)?;
+    cx.export_function("isDeterministic", api256::api256_is_deterministic)?;
     cx.export_function("generateKeyPair", api256::api256_generate_key_pair)?;
+    cx.export_function(
+        "deriveKeyPairFromPassphrase",
+        api256::api256_derive_key_pair_from_passphrase,
+    )?;
+    cx.export_function(
+        "deriveKeyPairFromPassphraseAsync",
+        api256::api256_derive_key_pair_from_passphrase_async,
+    )?;
+    cx.export_function(
+        "mnemonicToPrivateKey",
+        api256::api256_mnemonic_to_private_key,
+    )?;
+    cx.export_function("splitPrivateKey", api256::api256_split_private_key)?;
+    cx.export_function(
+        "combinePrivateKeyShares",
+        api256::api256_combine_private_key_shares,
+    )?;
+    cx.export_function(
+        "rotateUserPrivateKey",
+        api256::api256_rotate_user_private_key,
+    )?;
+    cx.export_function("rotateGroupKey", api256::api256_rotate_group_key)?;
+    cx.export_function("rotateGroupKeyAsync", api256::api256_rotate_group_key_async)?;
     cx.export_function(
         "generateEd25519KeyPair",
         api256::api256_generate_ed25519_key_pair,
     )?;
     cx.export_function("ed25519Sign", api256::api256_ed25519_sign)?;
     cx.export_function("ed25519Verify", api256::api256_ed25519_verify)?;
+    cx.export_function(
+        "verifyEncryptedValue",
+        api256::api256_verify_encrypted_value,
+    )?;
     cx.export_function(
         "computeEd25519PublicKey",
         api256::api256_compute_ed25519_public_key,
@@ -29,12 +106,89 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         "generateTransformKey",
         api256::api256_generate_transform_key,
     )?;
+    cx.export_function(
+        "generateTransformKeyAsync",
+        api256::api256_generate_transform_key_async,
+    )?;
     cx.export_function("computePublicKey", api256::api256_compute_public_key)?;
     cx.export_function("deriveSymmetricKey", api256::api256_derive_symmetric_key)?;
+    cx.export_function("derivePrivateKey", api256::api256_derive_private_key)?;
     cx.export_function("encrypt", api256::api256_encrypt)?;
+    cx.export_function("encryptAsync", api256::api256_encrypt_async)?;
+    cx.export_function("encryptMany", api256::api256_encrypt_many)?;
     cx.export_function("transform", api256::api256_transform)?;
+    cx.export_function("transformAsync", api256::api256_transform_async)?;
+    cx.export_function("transformMany", api256::api256_transform_many)?;
     cx.export_function("decrypt", api256::api256_decrypt)?;
+    cx.export_function("decryptAsync", api256::api256_decrypt_async)?;
+    cx.export_function("decryptMany", api256::api256_decrypt_many)?;
+    cx.export_function("decryptManyAsync", api256::api256_decrypt_many_async)?;
+    cx.export_function("encryptData", api256::api256_encrypt_data)?;
+    cx.export_function("decryptData", api256::api256_decrypt_data)?;
+    cx.export_function(
+        "createSharedDocument",
+        api256::api256_create_shared_document,
+    )?;
+    cx.export_function(
+        "decryptSharedDocument",
+        api256::api256_decrypt_shared_document,
+    )?;
+    cx.export_function("grantAccess", api256::api256_grant_access)?;
+    cx.export_function("revokeAccess", api256::api256_revoke_access)?;
+    cx.export_function("encryptDocument", api256::api256_encrypt_document)?;
+    cx.export_function("decryptDocument", api256::api256_decrypt_document)?;
+    cx.export_function(
+        "createStreamEncryptor",
+        api256::api256_create_stream_encryptor,
+    )?;
+    cx.export_function(
+        "createStreamDecryptor",
+        api256::api256_create_stream_decryptor,
+    )?;
+    cx.export_function("exportPrivateKey", api256::api256_export_private_key)?;
+    cx.export_function("importPrivateKey", api256::api256_import_private_key)?;
+    cx.export_function(
+        "importPrivateKeyAsync",
+        api256::api256_import_private_key_async,
+    )?;
     cx.export_function("schnorrSign", api256::api256_schnorr_sign)?;
     cx.export_function("schnorrVerify", api256::api256_schnorr_verify)?;
+    cx.export_function(
+        "createAugmentedKeyPair",
+        api256::api256_create_augmented_key_pair,
+    )?;
+    cx.export_function(
+        "generateAugmentedTransformKey",
+        api256::api256_generate_augmented_transform_key,
+    )?;
+    cx.export_function("signForAugmentedKey", api256::api256_sign_for_augmented_key)?;
+    cx.export_function(
+        "verifyAugmentedKeySignature",
+        api256::api256_verify_augmented_key_signature,
+    )?;
+    // stream handle member functions
+    cx.export_function("streamEncryptorUpdate", stream::stream_encryptor_update)?;
+    cx.export_function("streamEncryptorFinal", stream::stream_encryptor_final)?;
+    cx.export_function("streamDecryptorUpdate", stream::stream_decryptor_update)?;
+    cx.export_function("streamDecryptorFinal", stream::stream_decryptor_final)?;
+    // Api480 member functions
+    cx.export_function("createApi480", api480::api480_create_recrypt_api_480)?;
+    cx.export_function("generateKeyPair480", api480::api480_generate_key_pair)?;
+    cx.export_function(
+        "generateEd25519KeyPair480",
+        api480::api480_generate_ed25519_key_pair,
+    )?;
+    cx.export_function("generatePlaintext480", api480::api480_generate_plaintext)?;
+    cx.export_function(
+        "generateTransformKey480",
+        api480::api480_generate_transform_key,
+    )?;
+    cx.export_function("computePublicKey480", api480::api480_compute_public_key)?;
+    cx.export_function("deriveSymmetricKey480", api480::api480_derive_symmetric_key)?;
+    cx.export_function("encrypt480", api480::api480_encrypt)?;
+    cx.export_function("transform480", api480::api480_transform)?;
+    cx.export_function("decrypt480", api480::api480_decrypt)?;
+    cx.export_function("schnorrSign480", api480::api480_schnorr_sign)?;
+    cx.export_function("schnorrVerify480", api480::api480_schnorr_verify)?;
     Ok(())
 }
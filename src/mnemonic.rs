@@ -0,0 +1,117 @@
+use crate::handles;
+use crate::serialization::SerializationErr;
+use crate::util::{self, ErrorCode};
+use crate::wordlist;
+use neon::prelude::*;
+use sha2::{Digest, Sha256};
+
+// BIP39 style mnemonics for 32 byte private keys, so they can be written down and typed back in by hand. The key bytes
+// are followed by the first byte of their SHA-256 hash as a checksum, and the resulting 264 bits are split into 24
+// groups of 11 bits, each of which is an index into the English wordlist.
+
+pub const WORD_COUNT: usize = 24;
+const BITS_PER_WORD: usize = 11;
+
+/// Encode the 32 bytes as 24 space separated words.
+pub fn bytes_to_mnemonic(bytes: &[u8; 32]) -> String {
+    let checksum = Sha256::digest(bytes)[0];
+    let mut words = Vec::with_capacity(WORD_COUNT);
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for byte in bytes.iter().chain(std::iter::once(&checksum)) {
+        acc = (acc << 8) | *byte as u32;
+        acc_bits += 8;
+        while acc_bits >= BITS_PER_WORD {
+            acc_bits -= BITS_PER_WORD;
+            words.push(wordlist::ENGLISH[((acc >> acc_bits) & 0x7ff) as usize]);
+        }
+    }
+    words.join(" ")
+}
+
+/// Decode 24 words back into the 32 bytes they encode. Words are matched case insensitively. Fails if there aren't 24
+/// words, if a word isn't in the wordlist or if the checksum doesn't match.
+pub fn mnemonic_to_bytes(words: &[String], field_path: &str) -> Result<[u8; 32], SerializationErr> {
+    if words.len() != WORD_COUNT {
+        return Err(SerializationErr::new(
+            ErrorCode::InvalidMnemonic,
+            field_path,
+            format!(
+                "Provided mnemonic for '{}' must have {} words. Instead got {} words.",
+                field_path,
+                WORD_COUNT,
+                words.len()
+            ),
+        ));
+    }
+    let mut bytes = Vec::with_capacity(33);
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for (i, word) in words.iter().enumerate() {
+        let index = wordlist::ENGLISH
+            .binary_search(&word.to_lowercase().as_str())
+            .map_err(|_| {
+                SerializationErr::new(
+                    ErrorCode::InvalidMnemonic,
+                    &format!("{}[{}]", field_path, i),
+                    format!(
+                        "Provided word '{}' at index {} of '{}' isn't in the wordlist.",
+                        word, i, field_path
+                    ),
+                )
+            })?;
+        acc = (acc << BITS_PER_WORD) | index as u32;
+        acc_bits += BITS_PER_WORD;
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            bytes.push((acc >> acc_bits) as u8);
+        }
+    }
+    let mut key_bytes = [0u8; 32];
+    key_bytes.copy_from_slice(&bytes[..32]);
+    if Sha256::digest(key_bytes)[0] != bytes[32] {
+        return Err(SerializationErr::new(
+            ErrorCode::InvalidChecksum,
+            field_path,
+            format!(
+                "Provided mnemonic for '{}' has an invalid checksum. Check that the words were entered correctly.",
+                field_path
+            ),
+        ));
+    }
+    Ok(key_bytes)
+}
+
+/// Convert a mnemonic argument, which may be a single space separated string or an array of words, into its words.
+pub fn js_value_to_mnemonic_words<'a, T: Context<'a>>(
+    cx: &mut T,
+    value: Handle<'a, JsValue>,
+    field_path: &str,
+) -> NeonResult<Vec<String>> {
+    if let Ok(mnemonic) = value.downcast::<JsString, _>(cx) {
+        return Ok(mnemonic
+            .value(cx)
+            .split_whitespace()
+            .map(|word| word.to_string())
+            .collect());
+    }
+    let array: Handle<JsArray> = util::downcast_field(cx, value, field_path, "string or Array")?;
+    array
+        .to_vec(cx)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let word: Handle<JsString> =
+                util::downcast_field(cx, word, &format!("{}[{}]", field_path, i), "string")?;
+            Ok(word.value(cx).trim().to_string())
+        })
+        .collect()
+}
+
+/// Encode a 32 byte private key, or the key held by a PrivateKeyHandle, as a 24 word mnemonic.
+pub fn private_key_to_mnemonic(mut cx: FunctionContext) -> JsResult<JsString> {
+    let private_key_value: Handle<JsValue> = cx.argument::<JsValue>(0)?;
+    let private_key = handles::js_value_to_private_key(&mut cx, private_key_value, "privateKey")?;
+
+    Ok(cx.string(bytes_to_mnemonic(private_key.bytes())))
+}
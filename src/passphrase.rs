@@ -0,0 +1,356 @@
+use crate::aes;
+use crate::serialization::{ByteReader, SerializationErr};
+use crate::util::{self, ErrorCode, ErrorKind};
+use argon2::{Algorithm, Argon2, Params, Version};
+use neon::{prelude::*, types::JsBuffer};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use zeroize::Zeroizing;
+
+// Passphrase protected backups of private keys. The passphrase is stretched with Argon2id into an AES-256-GCM key which
+// encrypts the private key. Everything before the nonce is authenticated as AAD, so the KDF parameters and public key
+// can't be changed without the blob failing to decrypt. Version 1:
+//
+//   offset  size  field
+//   0       1     version (1)
+//   1       1     key type (1 = 256-bit recrypt private key, 2 = Ed25519 signing key pair)
+//   2       1     KDF (1 = Argon2id)
+//   3       4     memory cost in KiB
+//   7       4     time cost (iterations)
+//   11      4     parallelism
+//   15      16    salt
+//   31      n     public key (64 bytes of x and y for a recrypt key, 32 bytes for an Ed25519 key)
+//   31 + n  12    AES-GCM nonce
+//   43 + n  ...   encrypted private key (32 bytes for a recrypt key, 64 for an Ed25519 key) and the 16 byte GCM tag
+
+pub const KEY_BACKUP_VERSION: u8 = 1;
+const ARGON2ID_KDF: u8 = 1;
+pub const SALT_LEN: usize = 16;
+// Limits on the KDF parameters. Imports read them from the blob, so these bound how much memory and time a crafted blob
+// can make an import use. Exports use the same limits so every blob we write can be imported.
+const MAX_MEMORY_COST_KIB: u32 = 256 * 1024;
+const MAX_TIME_COST: u32 = 10;
+const MAX_PARALLELISM: u32 = 16;
+
+/// The type of key held in a backup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupKeyType {
+    Recrypt256 = 1,
+    Ed25519 = 2,
+}
+
+impl BackupKeyType {
+    fn public_key_len(self) -> usize {
+        match self {
+            BackupKeyType::Recrypt256 => 64,
+            BackupKeyType::Ed25519 => 32,
+        }
+    }
+
+    fn private_key_len(self) -> usize {
+        match self {
+            BackupKeyType::Recrypt256 => 32,
+            BackupKeyType::Ed25519 => 64,
+        }
+    }
+}
+
+/// Argon2id parameters. Defaults to the OWASP recommendation of 19 MiB of memory and 2 iterations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> KdfParams {
+        KdfParams {
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    fn to_argon2(self) -> Result<Argon2<'static>, String> {
+        if self.memory_cost > MAX_MEMORY_COST_KIB
+            || self.time_cost > MAX_TIME_COST
+            || self.parallelism > MAX_PARALLELISM
+        {
+            return Err(format!(
+                "KDF parameters may be at most {} KiB of memory, {} iterations and a parallelism of {}.",
+                MAX_MEMORY_COST_KIB, MAX_TIME_COST, MAX_PARALLELISM
+            ));
+        }
+        let params = Params::new(self.memory_cost, self.time_cost, self.parallelism, Some(32))
+            .map_err(|e| format!("Invalid KDF parameters. {}", e))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// Make sure Argon2id accepts these parameters and they are within our limits.
+    pub fn validate(self) -> Result<(), String> {
+        self.to_argon2().map(|_| ())
+    }
+
+    /// Stretch the passphrase into a 32 byte key, which is cleared when dropped.
+    pub fn derive_key(self, passphrase: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, String> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        self.to_argon2()?
+            .hash_password_into(passphrase, salt, &mut *key)
+            .map_err(|e| format!("Failed to derive a key from the passphrase. {}", e))?;
+        Ok(key)
+    }
+}
+
+/// Encrypt the private key under the passphrase, recording the KDF parameters and public key in the blob. The
+/// parameters must have been validated.
+pub fn export_key(
+    key_type: BackupKeyType,
+    private_key: &[u8],
+    public_key: &[u8],
+    passphrase: &[u8],
+    params: KdfParams,
+) -> Vec<u8> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = params
+        .derive_key(passphrase, &salt)
+        .expect("KDF parameters were validated");
+
+    let mut blob = vec![KEY_BACKUP_VERSION, key_type as u8, ARGON2ID_KDF];
+    blob.extend_from_slice(&params.memory_cost.to_be_bytes());
+    blob.extend_from_slice(&params.time_cost.to_be_bytes());
+    blob.extend_from_slice(&params.parallelism.to_be_bytes());
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(public_key);
+    let encrypted = aes::encrypt(&key, private_key, &blob);
+    blob.extend_from_slice(&encrypted);
+    blob
+}
+
+/// A private key recovered from a backup, along with the public key recorded next to it. The caller must check that
+/// the public key matches the private key. The private key bytes are cleared when dropped.
+pub struct ImportedKey {
+    pub key_type: BackupKeyType,
+    pub private_key: Zeroizing<Vec<u8>>,
+    pub public_key: Vec<u8>,
+}
+
+/// Why a backup couldn't be imported.
+pub enum ImportErr {
+    Parse(SerializationErr),
+    Kdf(String),
+    WrongPassphrase,
+}
+
+impl From<SerializationErr> for ImportErr {
+    fn from(e: SerializationErr) -> ImportErr {
+        ImportErr::Parse(e)
+    }
+}
+
+/// Parse the blob and decrypt the private key in it with the passphrase.
+pub fn import_key(
+    blob: &[u8],
+    passphrase: &[u8],
+    field_path: &str,
+) -> Result<ImportedKey, ImportErr> {
+    let mut reader = ByteReader::new(blob, field_path);
+    reader.read_version(KEY_BACKUP_VERSION)?;
+    let key_type = match reader.read_u8("the key type")? {
+        1 => BackupKeyType::Recrypt256,
+        2 => BackupKeyType::Ed25519,
+        other => {
+            return Err(reader
+                .invalid(
+                    ErrorCode::InvalidEncoding,
+                    format!(
+                        "Provided bytes for '{}' have unknown key type {}.",
+                        field_path, other
+                    ),
+                )
+                .into());
+        }
+    };
+    let kdf = reader.read_u8("the KDF")?;
+    if kdf != ARGON2ID_KDF {
+        return Err(reader
+            .invalid(
+                ErrorCode::InvalidEncoding,
+                format!(
+                    "Provided bytes for '{}' have unknown KDF {}.",
+                    field_path, kdf
+                ),
+            )
+            .into());
+    }
+    let params = KdfParams {
+        memory_cost: u32::from_be_bytes(reader.read_array("the memory cost")?),
+        time_cost: u32::from_be_bytes(reader.read_array("the time cost")?),
+        parallelism: u32::from_be_bytes(reader.read_array("the parallelism")?),
+    };
+    let salt = reader.read_slice(SALT_LEN, "the salt")?;
+    let public_key = reader.read_slice(key_type.public_key_len(), "the public key")?;
+    let header_len = reader.offset();
+    let encrypted = reader.read_slice(
+        aes::NONCE_LEN + key_type.private_key_len() + aes::TAG_LEN,
+        "the encrypted private key",
+    )?;
+    reader.finish()?;
+
+    let key = params
+        .derive_key(passphrase, salt)
+        .map_err(ImportErr::Kdf)?;
+    let private_key = aes::decrypt(&key, encrypted, &blob[..header_len])
+        .map(Zeroizing::new)
+        .map_err(|_| ImportErr::WrongPassphrase)?;
+    Ok(ImportedKey {
+        key_type,
+        private_key,
+        public_key: public_key.to_vec(),
+    })
+}
+
+/// Unwrap the result of importing a backup, throwing a JS error which names the blob on failure.
+pub fn import_result_or_throw<'a, T: Context<'a>, R>(
+    cx: &mut T,
+    result: Result<R, ImportErr>,
+    field_path: &str,
+) -> NeonResult<R> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(ImportErr::Parse(e)) => crate::serialization::serialization_result_or_throw(cx, Err(e)),
+        Err(ImportErr::Kdf(message)) => util::throw_error(
+            cx,
+            ErrorKind::Error,
+            ErrorCode::InvalidEncoding,
+            Some(field_path),
+            &message,
+        ),
+        Err(ImportErr::WrongPassphrase) => util::throw_error(
+            cx,
+            ErrorKind::Error,
+            ErrorCode::InvalidCiphertext,
+            Some(field_path),
+            &format!(
+                "Provided value for '{}' failed to decrypt. The passphrase is wrong or the backup was modified.",
+                field_path
+            ),
+        ),
+    }
+}
+
+/// Convert a passphrase argument, which may be a string or a Buffer, into its bytes. Strings are UTF-8 encoded.
+pub fn js_value_to_passphrase<'a, T: Context<'a>>(
+    cx: &mut T,
+    value: Handle<'a, JsValue>,
+    field_path: &str,
+) -> NeonResult<Zeroizing<Vec<u8>>> {
+    if let Ok(passphrase) = value.downcast::<JsString, _>(cx) {
+        return Ok(Zeroizing::new(passphrase.value(cx).into_bytes()));
+    }
+    let buffer: Handle<JsBuffer> = util::downcast_field(cx, value, field_path, "string or Buffer")?;
+    Ok(Zeroizing::new(util::buffer_to_variable_bytes(cx, buffer)))
+}
+
+/// Convert a salt argument into its bytes. Throws a RangeError if it's shorter than Argon2id allows.
+pub fn buffer_to_salt<'a, T: Context<'a>>(
+    cx: &mut T,
+    buffer: Handle<JsBuffer>,
+    field_path: &str,
+) -> NeonResult<Vec<u8>> {
+    let salt = util::buffer_to_variable_bytes(cx, buffer);
+    if salt.len() < argon2::MIN_SALT_LEN {
+        return util::throw_error(
+            cx,
+            ErrorKind::RangeError,
+            ErrorCode::InvalidBufferLength,
+            Some(field_path),
+            &format!(
+                "Provided Buffer for '{}' must be at least {} bytes. Instead got {} bytes.",
+                field_path,
+                argon2::MIN_SALT_LEN,
+                salt.len()
+            ),
+        );
+    }
+    Ok(salt)
+}
+
+/// Read `{memoryCost, timeCost, parallelism}` KDF parameters from an optional object argument, using the default for
+/// anything which is missing. Throws a RangeError if Argon2id won't accept the parameters or they exceed our limits.
+pub fn js_value_to_kdf_params<'a, T: Context<'a>>(
+    cx: &mut T,
+    value: Option<Handle<'a, JsValue>>,
+    field_path: &str,
+) -> NeonResult<KdfParams> {
+    let defaults = KdfParams::default();
+    let params =
+        match util::downcast_optional_argument::<JsObject, _>(cx, value, field_path, "object")? {
+            None => defaults,
+            Some(params_obj) => KdfParams {
+                memory_cost: get_u32_param(
+                    cx,
+                    params_obj,
+                    field_path,
+                    "memoryCost",
+                    defaults.memory_cost,
+                )?,
+                time_cost: get_u32_param(
+                    cx,
+                    params_obj,
+                    field_path,
+                    "timeCost",
+                    defaults.time_cost,
+                )?,
+                parallelism: get_u32_param(
+                    cx,
+                    params_obj,
+                    field_path,
+                    "parallelism",
+                    defaults.parallelism,
+                )?,
+            },
+        };
+    match params.validate() {
+        Ok(()) => Ok(params),
+        Err(message) => util::throw_error(
+            cx,
+            ErrorKind::RangeError,
+            ErrorCode::InputWrongSize,
+            Some(field_path),
+            &message,
+        ),
+    }
+}
+
+fn get_u32_param<'a, T: Context<'a>>(
+    cx: &mut T,
+    params_obj: Handle<JsObject>,
+    field_path: &str,
+    key: &str,
+    default: u32,
+) -> NeonResult<u32> {
+    let value = params_obj.get_value(cx, key)?;
+    if value.is_a::<JsUndefined, _>(cx) {
+        return Ok(default);
+    }
+    let full_path = format!("{}.{}", field_path, key);
+    let number: Handle<JsNumber> = util::downcast_field(cx, value, &full_path, "number")?;
+    let number = number.value(cx);
+    if number < 0.0 || number.fract() != 0.0 || number > u32::MAX as f64 {
+        return util::throw_error(
+            cx,
+            ErrorKind::TypeError,
+            ErrorCode::InvalidFieldType,
+            Some(&full_path),
+            &format!(
+                "Provided value for '{}' must be a non-negative integer.",
+                full_path
+            ),
+        );
+    }
+    Ok(number as u32)
+}
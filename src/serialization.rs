@@ -0,0 +1,459 @@
+use crate::util::{self, ErrorCode, ErrorKind};
+use neon::prelude::*;
+use recrypt::api::{
+    AuthHash, Ed25519Signature, EncryptedMessage, EncryptedTempKey, EncryptedValue, HashedValue,
+    PublicKey, PublicSigningKey, TransformBlock, TransformKey,
+};
+use recrypt::nonemptyvec::NonEmptyVec;
+
+// Versioned binary layouts for the recrypt values we hand back to JS, so callers can store them without having to pack
+// the nested JS objects themselves. Every layout starts with a version byte. All multi-byte integers are big endian.
+//
+// EncryptedValue, version 1:
+//
+//   offset  size  field
+//   0       1     version (1)
+//   1       1     variant (0 = EncryptedOnceValue, 1 = TransformedValue)
+//   2       32    ephemeralPublicKey.x
+//   34      32    ephemeralPublicKey.y
+//   66      384   encryptedMessage
+//   450     32    authHash
+//   482     32    publicSigningKey
+//   514     64    signature
+//
+// A TransformedValue is followed by a 2 byte count of transform blocks (at least 1) and then that many 896 byte blocks:
+//
+//   0       32    publicKey.x
+//   32      32    publicKey.y
+//   64      384   encryptedTempKey
+//   448     32    randomTransformPublicKey.x
+//   480     32    randomTransformPublicKey.y
+//   512     384   randomTransformEncryptedTempKey
+//
+// TransformKey, version 1. Unlike `TransformKey::to_bytes()`, which is only the input to the signature, this holds all
+// six fields and can be parsed back:
+//
+//   offset  size  field
+//   0       1     version (1)
+//   1       32    ephemeralPublicKey.x
+//   33      32    ephemeralPublicKey.y
+//   65      32    toPublicKey.x
+//   97      32    toPublicKey.y
+//   129     384   encryptedTempKey
+//   513     128   hashedTempKey
+//   641     32    publicSigningKey
+//   673     64    signature
+
+pub const ENCRYPTED_VALUE_VERSION: u8 = 1;
+pub const TRANSFORM_KEY_VERSION: u8 = 1;
+const ENCRYPTED_ONCE_VALUE_VARIANT: u8 = 0;
+const TRANSFORMED_VALUE_VARIANT: u8 = 1;
+
+/// Why a serialized value couldn't be parsed. `field` is the path to the part of the value which was being read.
+#[derive(Debug)]
+pub struct SerializationErr {
+    pub code: ErrorCode,
+    pub field: String,
+    pub message: String,
+}
+
+impl SerializationErr {
+    pub fn new(code: ErrorCode, field: &str, message: String) -> SerializationErr {
+        SerializationErr {
+            code,
+            field: field.to_string(),
+            message,
+        }
+    }
+}
+
+/// Unwrap the result of parsing a serialized value. On failure throws a JS error with the code and field of the
+/// SerializationErr. Length problems are thrown as RangeErrors.
+pub fn serialization_result_or_throw<'a, T: Context<'a>, R>(
+    cx: &mut T,
+    result: Result<R, SerializationErr>,
+) -> NeonResult<R> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            let kind = match e.code {
+                ErrorCode::InvalidBufferLength | ErrorCode::InputWrongSize => ErrorKind::RangeError,
+                _ => ErrorKind::Error,
+            };
+            util::throw_error(cx, kind, e.code, Some(&e.field), &e.message)
+        }
+    }
+}
+
+/// Reads fixed size fields off the front of a serialized value, keeping track of the offset so errors can say where
+/// the bytes ran out.
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    field_path: &'a str,
+}
+
+impl<'a> ByteReader<'a> {
+    /// `field_path` is the name of the argument the bytes came from and is used in every error.
+    pub fn new(bytes: &'a [u8], field_path: &'a str) -> ByteReader<'a> {
+        ByteReader {
+            bytes,
+            offset: 0,
+            field_path,
+        }
+    }
+
+    /// Read the next `len` bytes. `what` names the field being read for the error message.
+    pub fn read_slice(&mut self, len: usize, what: &str) -> Result<&'a [u8], SerializationErr> {
+        let remaining = self.bytes.len() - self.offset;
+        if remaining < len {
+            return Err(SerializationErr::new(
+                ErrorCode::InvalidBufferLength,
+                self.field_path,
+                format!(
+                    "Provided bytes for '{}' are truncated. Expected {} bytes for {} at offset {}, but only {} remain.",
+                    self.field_path, len, what, self.offset, remaining
+                ),
+            ));
+        }
+        let slice = &self.bytes[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(slice)
+    }
+
+    pub fn read_array<const N: usize>(&mut self, what: &str) -> Result<[u8; N], SerializationErr> {
+        let mut result = [0u8; N];
+        result.copy_from_slice(self.read_slice(N, what)?);
+        Ok(result)
+    }
+
+    pub fn read_u8(&mut self, what: &str) -> Result<u8, SerializationErr> {
+        Ok(self.read_array::<1>(what)?[0])
+    }
+
+    pub fn read_u16(&mut self, what: &str) -> Result<u16, SerializationErr> {
+        Ok(u16::from_be_bytes(self.read_array(what)?))
+    }
+
+    /// Read the leading version byte and make sure it's one we know how to parse.
+    pub fn read_version(&mut self, expected: u8) -> Result<u8, SerializationErr> {
+        let version = self.read_u8("the version")?;
+        if version != expected {
+            return Err(SerializationErr::new(
+                ErrorCode::UnsupportedVersion,
+                self.field_path,
+                format!(
+                    "Provided bytes for '{}' have unsupported version {}. Only version {} is supported.",
+                    self.field_path, version, expected
+                ),
+            ));
+        }
+        Ok(version)
+    }
+
+    /// The number of bytes read so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Read every byte which hasn't been read yet.
+    pub fn read_rest(&mut self) -> &'a [u8] {
+        let rest = &self.bytes[self.offset..];
+        self.offset = self.bytes.len();
+        rest
+    }
+
+    /// Make sure every byte has been read.
+    pub fn finish(self) -> Result<(), SerializationErr> {
+        let trailing = self.bytes.len() - self.offset;
+        if trailing > 0 {
+            return Err(SerializationErr::new(
+                ErrorCode::InvalidBufferLength,
+                self.field_path,
+                format!(
+                    "Provided bytes for '{}' have {} unexpected trailing bytes after offset {}.",
+                    self.field_path, trailing, self.offset
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Create an error for a value which was read successfully but isn't valid.
+    pub fn invalid(&self, code: ErrorCode, message: String) -> SerializationErr {
+        SerializationErr::new(code, self.field_path, message)
+    }
+}
+
+fn write_public_key(bytes: &mut Vec<u8>, public_key: &PublicKey) {
+    let (x, y) = public_key.bytes_x_y();
+    bytes.extend_from_slice(x);
+    bytes.extend_from_slice(y);
+}
+
+fn read_public_key(reader: &mut ByteReader, what: &str) -> Result<PublicKey, SerializationErr> {
+    let x = reader.read_array(&format!("{}.x", what))?;
+    let y = reader.read_array(&format!("{}.y", what))?;
+    PublicKey::new((x, y)).map_err(|e| {
+        reader.invalid(
+            ErrorCode::InvalidPublicKey,
+            format!("Serialized {} is invalid. {}", what, e),
+        )
+    })
+}
+
+/// Serialize the provided EncryptedValue using the version 1 layout. Fails if there are more transform blocks than the 2
+/// byte count can hold.
+pub fn encrypted_value_to_bytes(
+    encrypted_value: &EncryptedValue,
+    field_path: &str,
+) -> Result<Vec<u8>, SerializationErr> {
+    let (
+        variant,
+        ephemeral_public_key,
+        encrypted_message,
+        auth_hash,
+        public_signing_key,
+        signature,
+    ) = match encrypted_value {
+        EncryptedValue::EncryptedOnceValue {
+            ephemeral_public_key,
+            encrypted_message,
+            auth_hash,
+            public_signing_key,
+            signature,
+        } => (
+            ENCRYPTED_ONCE_VALUE_VARIANT,
+            ephemeral_public_key,
+            encrypted_message,
+            auth_hash,
+            public_signing_key,
+            signature,
+        ),
+        EncryptedValue::TransformedValue {
+            ephemeral_public_key,
+            encrypted_message,
+            auth_hash,
+            public_signing_key,
+            signature,
+            ..
+        } => (
+            TRANSFORMED_VALUE_VARIANT,
+            ephemeral_public_key,
+            encrypted_message,
+            auth_hash,
+            public_signing_key,
+            signature,
+        ),
+    };
+
+    let mut bytes = vec![ENCRYPTED_VALUE_VERSION, variant];
+    write_public_key(&mut bytes, ephemeral_public_key);
+    bytes.extend_from_slice(encrypted_message.bytes());
+    bytes.extend_from_slice(auth_hash.bytes());
+    bytes.extend_from_slice(public_signing_key.bytes());
+    bytes.extend_from_slice(signature.bytes());
+
+    if let EncryptedValue::TransformedValue {
+        transform_blocks, ..
+    } = encrypted_value
+    {
+        let blocks = transform_blocks.to_vec();
+        if blocks.len() > u16::MAX as usize {
+            return Err(SerializationErr::new(
+                ErrorCode::InputWrongSize,
+                &format!("{}.transformBlocks", field_path),
+                format!(
+                    "Provided '{}.transformBlocks' has {} blocks, but at most {} can be serialized.",
+                    field_path,
+                    blocks.len(),
+                    u16::MAX
+                ),
+            ));
+        }
+        bytes.extend_from_slice(&(blocks.len() as u16).to_be_bytes());
+        for block in blocks.iter() {
+            write_public_key(&mut bytes, block.public_key());
+            bytes.extend_from_slice(block.encrypted_temp_key().bytes());
+            write_public_key(&mut bytes, block.random_transform_public_key());
+            bytes.extend_from_slice(block.encrypted_random_transform_temp_key().bytes());
+        }
+    }
+    Ok(bytes)
+}
+
+/// Build the bytes recrypt signs with the Ed25519 key when it creates an EncryptedValue: the public signing key,
+/// ephemeral public key, encrypted message and auth hash, followed by every field of each transform block for a
+/// TransformedValue. Unlike the serialized layout there's no version, variant or block count.
+pub fn encrypted_value_signed_bytes(encrypted_value: &EncryptedValue) -> Vec<u8> {
+    let (ephemeral_public_key, encrypted_message, auth_hash, public_signing_key) =
+        match encrypted_value {
+            EncryptedValue::EncryptedOnceValue {
+                ephemeral_public_key,
+                encrypted_message,
+                auth_hash,
+                public_signing_key,
+                ..
+            }
+            | EncryptedValue::TransformedValue {
+                ephemeral_public_key,
+                encrypted_message,
+                auth_hash,
+                public_signing_key,
+                ..
+            } => (
+                ephemeral_public_key,
+                encrypted_message,
+                auth_hash,
+                public_signing_key,
+            ),
+        };
+
+    let mut bytes = public_signing_key.bytes().to_vec();
+    write_public_key(&mut bytes, ephemeral_public_key);
+    bytes.extend_from_slice(encrypted_message.bytes());
+    bytes.extend_from_slice(auth_hash.bytes());
+
+    if let EncryptedValue::TransformedValue {
+        transform_blocks, ..
+    } = encrypted_value
+    {
+        for block in transform_blocks.to_vec().iter() {
+            write_public_key(&mut bytes, block.public_key());
+            bytes.extend_from_slice(block.encrypted_temp_key().bytes());
+            write_public_key(&mut bytes, block.random_transform_public_key());
+            bytes.extend_from_slice(block.encrypted_random_transform_temp_key().bytes());
+        }
+    }
+    bytes
+}
+
+/// Parse an EncryptedValue from the version 1 layout. Rejects unknown versions and variants, truncated input, trailing
+/// bytes and points which aren't on the curve.
+pub fn encrypted_value_from_bytes(
+    bytes: &[u8],
+    field_path: &str,
+) -> Result<EncryptedValue, SerializationErr> {
+    let mut reader = ByteReader::new(bytes, field_path);
+    reader.read_version(ENCRYPTED_VALUE_VERSION)?;
+    let variant = reader.read_u8("the variant")?;
+    if variant != ENCRYPTED_ONCE_VALUE_VARIANT && variant != TRANSFORMED_VALUE_VARIANT {
+        return Err(reader.invalid(
+            ErrorCode::InvalidEncoding,
+            format!(
+                "Provided bytes for '{}' have unknown EncryptedValue variant {}.",
+                field_path, variant
+            ),
+        ));
+    }
+
+    let ephemeral_public_key = read_public_key(&mut reader, "ephemeralPublicKey")?;
+    let encrypted_message = EncryptedMessage::new(reader.read_array("encryptedMessage")?);
+    let auth_hash = AuthHash::new(reader.read_array("authHash")?);
+    let public_signing_key = PublicSigningKey::new(reader.read_array("publicSigningKey")?);
+    let signature = Ed25519Signature::new(reader.read_array("signature")?);
+
+    let encrypted_value = if variant == ENCRYPTED_ONCE_VALUE_VARIANT {
+        EncryptedValue::EncryptedOnceValue {
+            ephemeral_public_key,
+            encrypted_message,
+            auth_hash,
+            public_signing_key,
+            signature,
+        }
+    } else {
+        let block_count = reader.read_u16("the transform block count")?;
+        let mut blocks = Vec::with_capacity(block_count as usize);
+        for i in 0..block_count {
+            let block_path = format!("transformBlocks[{}]", i);
+            let public_key = read_public_key(&mut reader, &format!("{}.publicKey", block_path))?;
+            let encrypted_temp_key = EncryptedTempKey::new(
+                reader.read_array(&format!("{}.encryptedTempKey", block_path))?,
+            );
+            let random_transform_public_key = read_public_key(
+                &mut reader,
+                &format!("{}.randomTransformPublicKey", block_path),
+            )?;
+            let random_transform_encrypted_temp_key = EncryptedTempKey::new(
+                reader.read_array(&format!("{}.randomTransformEncryptedTempKey", block_path))?,
+            );
+            blocks.push(
+                TransformBlock::new(
+                    &public_key,
+                    &encrypted_temp_key,
+                    &random_transform_public_key,
+                    &random_transform_encrypted_temp_key,
+                )
+                .map_err(|e| {
+                    reader.invalid(
+                        ErrorCode::InvalidPublicKey,
+                        format!("Serialized {} is invalid. {}", block_path, e),
+                    )
+                })?,
+            );
+        }
+        let transform_blocks = NonEmptyVec::try_from(&blocks).map_err(|_| {
+            reader.invalid(
+                ErrorCode::InvalidEncoding,
+                format!(
+                    "Provided bytes for '{}' are a TransformedValue without any transform blocks.",
+                    field_path
+                ),
+            )
+        })?;
+        EncryptedValue::TransformedValue {
+            ephemeral_public_key,
+            encrypted_message,
+            auth_hash,
+            public_signing_key,
+            signature,
+            transform_blocks,
+        }
+    };
+
+    reader.finish()?;
+    Ok(encrypted_value)
+}
+
+/// Serialize the provided TransformKey using the version 1 layout.
+pub fn transform_key_to_bytes(transform_key: &TransformKey) -> Vec<u8> {
+    let mut bytes = vec![TRANSFORM_KEY_VERSION];
+    write_public_key(&mut bytes, transform_key.ephemeral_public_key());
+    write_public_key(&mut bytes, transform_key.to_public_key());
+    bytes.extend_from_slice(transform_key.encrypted_temp_key().bytes());
+    bytes.extend_from_slice(transform_key.hashed_temp_key().bytes());
+    bytes.extend_from_slice(transform_key.public_signing_key().bytes());
+    bytes.extend_from_slice(transform_key.signature().bytes());
+    bytes
+}
+
+/// Parse a TransformKey from the version 1 layout. Both public keys and the hashed temp key are validated here so that
+/// corrupt bytes are caught when they're loaded rather than when the key is used.
+pub fn transform_key_from_bytes(
+    bytes: &[u8],
+    field_path: &str,
+) -> Result<TransformKey, SerializationErr> {
+    let mut reader = ByteReader::new(bytes, field_path);
+    reader.read_version(TRANSFORM_KEY_VERSION)?;
+    let ephemeral_public_key = read_public_key(&mut reader, "ephemeralPublicKey")?;
+    let to_public_key = read_public_key(&mut reader, "toPublicKey")?;
+    let encrypted_temp_key = EncryptedTempKey::new(reader.read_array("encryptedTempKey")?);
+    let hashed_temp_key = HashedValue::new(reader.read_array("hashedTempKey")?).map_err(|e| {
+        reader.invalid(
+            ErrorCode::InvalidHashedValue,
+            format!("Serialized hashedTempKey is invalid. {}", e),
+        )
+    })?;
+    let public_signing_key = PublicSigningKey::new(reader.read_array("publicSigningKey")?);
+    let signature = Ed25519Signature::new(reader.read_array("signature")?);
+    reader.finish()?;
+
+    Ok(TransformKey::new(
+        ephemeral_public_key,
+        to_public_key,
+        encrypted_temp_key,
+        hashed_temp_key,
+        public_signing_key,
+        signature,
+    ))
+}
@@ -0,0 +1,372 @@
+use crate::serialization::{ByteReader, SerializationErr};
+use crate::util::{self, ErrorCode, ErrorKind};
+use neon::prelude::*;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use std::ops::{Add, Mul, Sub};
+
+// Shamir secret sharing of 256-bit private keys. The secret is the constant term of a random polynomial of degree
+// `threshold - 1` over the curve order Fr, which is the field `PrivateKey::augment_plus` and `augment_minus` work in, and
+// each share is the polynomial evaluated at its index. Any `threshold` shares recover the secret by Lagrange
+// interpolation. A private key is recovered modulo the curve order, so it may not have the same bytes as the key which
+// was split, but it always has the same public key. Each share is versioned and self checking, version 1:
+//
+//   offset  size  field
+//   0       1     version (1)
+//   1       1     threshold
+//   2       1     index (1-255)
+//   3       64    public key of the split private key (x then y)
+//   67      32    share value
+//   99      4     checksum, the first 4 bytes of the SHA-256 of everything before it
+
+pub const SHARE_VERSION: u8 = 1;
+const CHECKSUM_LEN: usize = 4;
+
+/// An element of Fr, the scalar field of the 256-bit curve. recrypt doesn't export its Fr256, so this implements the
+/// little arithmetic sharing needs with Montgomery multiplication over 64 bit limbs, least significant first.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fr256([u64; 4]);
+
+impl Fr256 {
+    // r = 65000549695646603732796438742359905742570406053903786389881062969044166799969
+    const MODULUS: [u64; 4] = [
+        0x1a2ef45b57ac7261,
+        0x2e8d8e12f82b3924,
+        0xaa6fecb86184dc21,
+        0x8fb501e34aa387f9,
+    ];
+    // 2^512 mod r, used to convert out of the Montgomery domain after a multiplication.
+    const R_SQUARED: [u64; 4] = [
+        0xb5f030132affbc35,
+        0x85a1f7da0792e95d,
+        0x26841e5fa6ee4895,
+        0x3d8f6c73765aefd5,
+    ];
+    // -r^-1 mod 2^64
+    const M0_INV: u64 = 0x056417b72d284e5f;
+
+    fn zero() -> Fr256 {
+        Fr256([0; 4])
+    }
+
+    fn one() -> Fr256 {
+        Fr256::from(1)
+    }
+
+    fn from(small: u8) -> Fr256 {
+        Fr256([small as u64, 0, 0, 0])
+    }
+
+    /// Interpret the big endian bytes as an integer and reduce it. 2^256 < 2r, so one subtraction is enough.
+    fn from_bytes(bytes: &[u8; 32]) -> Fr256 {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 32 - (i + 1) * 8;
+            *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+        }
+        Fr256::reduce_once(limbs, 0)
+    }
+
+    fn to_bytes(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, limb) in self.0.iter().enumerate() {
+            let start = 32 - (i + 1) * 8;
+            bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Subtract r from the value with the extra high limb `hi` if it's at least r, without branching on the value.
+    fn reduce_once(value: [u64; 4], hi: u64) -> Fr256 {
+        let mut diff = [0u64; 4];
+        let mut borrow = 0;
+        for i in 0..4 {
+            (diff[i], borrow) = sbb(value[i], Fr256::MODULUS[i], borrow);
+        }
+        let (_, borrow) = sbb(hi, 0, borrow);
+        // All ones if the subtraction went negative, in which case the value was already reduced.
+        let keep = borrow.wrapping_neg();
+        let mut result = [0u64; 4];
+        for i in 0..4 {
+            result[i] = (value[i] & keep) | (diff[i] & !keep);
+        }
+        Fr256(result)
+    }
+
+    /// Add r to the value if `mask` is all ones, or nothing if it's zero.
+    fn add_masked_modulus(mut value: [u64; 4], mask: u64) -> Fr256 {
+        let mut carry = 0;
+        for (limb, modulus_limb) in value.iter_mut().zip(Fr256::MODULUS) {
+            (*limb, carry) = adc(*limb, modulus_limb & mask, carry);
+        }
+        Fr256(value)
+    }
+
+    /// Montgomery multiplication, a * b / 2^256 mod r, using the CIOS method.
+    fn mont_mul(a: &[u64; 4], b: &[u64; 4]) -> Fr256 {
+        let mut t = [0u64; 6];
+        for b_i in b {
+            let mut carry = 0;
+            for j in 0..4 {
+                (t[j], carry) = mac(t[j], a[j], *b_i, carry);
+            }
+            (t[4], t[5]) = adc(t[4], carry, 0);
+            let m = t[0].wrapping_mul(Fr256::M0_INV);
+            let (_, mut carry) = mac(t[0], m, Fr256::MODULUS[0], 0);
+            for j in 1..4 {
+                (t[j - 1], carry) = mac(t[j], m, Fr256::MODULUS[j], carry);
+            }
+            let (high, overflow) = adc(t[4], carry, 0);
+            t[3] = high;
+            t[4] = t[5] + overflow;
+        }
+        Fr256::reduce_once([t[0], t[1], t[2], t[3]], t[4])
+    }
+
+    /// The multiplicative inverse, computed as self^(r - 2). Zero has no inverse and gives zero.
+    fn inv(self) -> Fr256 {
+        let mut exponent = Fr256::MODULUS;
+        exponent[0] -= 2;
+        let mut result = Fr256::one();
+        for limb in exponent.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result * result;
+                if (limb >> bit) & 1 == 1 {
+                    result = result * self;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Add for Fr256 {
+    type Output = Fr256;
+    fn add(self, other: Fr256) -> Fr256 {
+        let mut sum = [0u64; 4];
+        let mut carry = 0;
+        for (i, limb) in sum.iter_mut().enumerate() {
+            (*limb, carry) = adc(self.0[i], other.0[i], carry);
+        }
+        Fr256::reduce_once(sum, carry)
+    }
+}
+
+impl Sub for Fr256 {
+    type Output = Fr256;
+    fn sub(self, other: Fr256) -> Fr256 {
+        let mut diff = [0u64; 4];
+        let mut borrow = 0;
+        for (i, limb) in diff.iter_mut().enumerate() {
+            (*limb, borrow) = sbb(self.0[i], other.0[i], borrow);
+        }
+        // Add r back if the subtraction went negative.
+        Fr256::add_masked_modulus(diff, borrow.wrapping_neg())
+    }
+}
+
+impl Mul for Fr256 {
+    type Output = Fr256;
+    fn mul(self, other: Fr256) -> Fr256 {
+        let product = Fr256::mont_mul(&self.0, &other.0);
+        Fr256::mont_mul(&product.0, &Fr256::R_SQUARED)
+    }
+}
+
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let sum = a as u128 + b as u128 + carry as u128;
+    (sum as u64, (sum >> 64) as u64)
+}
+
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let diff = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (diff as u64, (diff >> 127) as u64)
+}
+
+fn mac(acc: u64, a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let sum = acc as u128 + a as u128 * b as u128 + carry as u128;
+    (sum as u64, (sum >> 64) as u64)
+}
+
+/// A parsed share.
+pub struct Share {
+    pub threshold: u8,
+    pub index: u8,
+    pub public_key: [u8; 64],
+    value: Fr256,
+}
+
+/// Generate a uniformly random element of Fr by rejecting byte strings which aren't already reduced.
+fn random_fr() -> Fr256 {
+    loop {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let fr = Fr256::from_bytes(&bytes);
+        if fr.to_bytes() == bytes {
+            return fr;
+        }
+    }
+}
+
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    checksum.copy_from_slice(&Sha256::digest(bytes)[..CHECKSUM_LEN]);
+    checksum
+}
+
+/// Split the secret into `share_count` serialized shares, any `threshold` of which recover it. The caller must make sure
+/// that `1 < threshold <= share_count`.
+pub fn split_secret(
+    secret: &[u8; 32],
+    public_key: &[u8; 64],
+    threshold: u8,
+    share_count: u8,
+) -> Vec<Vec<u8>> {
+    let coefficients: Vec<Fr256> = std::iter::once(Fr256::from_bytes(secret))
+        .chain((1..threshold).map(|_| random_fr()))
+        .collect();
+    (1..=share_count)
+        .map(|index| {
+            let x = Fr256::from(index);
+            // Horner's method, starting from the highest degree coefficient.
+            let value = coefficients
+                .iter()
+                .rev()
+                .fold(Fr256::zero(), |acc, coefficient| acc * x + *coefficient);
+            let mut share = Vec::with_capacity(103);
+            share.push(SHARE_VERSION);
+            share.push(threshold);
+            share.push(index);
+            share.extend_from_slice(public_key);
+            share.extend_from_slice(&value.to_bytes());
+            let checksum = checksum(&share);
+            share.extend_from_slice(&checksum);
+            share
+        })
+        .collect()
+}
+
+/// Parse a serialized share and verify its checksum.
+pub fn parse_share(bytes: &[u8], field_path: &str) -> Result<Share, SerializationErr> {
+    let mut reader = ByteReader::new(bytes, field_path);
+    reader.read_version(SHARE_VERSION)?;
+    let threshold = reader.read_u8("the threshold")?;
+    let index = reader.read_u8("the index")?;
+    let public_key = reader.read_array("the public key")?;
+    let value_bytes: [u8; 32] = reader.read_array("the share value")?;
+    let checksum_offset = reader.offset();
+    let expected_checksum: [u8; CHECKSUM_LEN] = reader.read_array("the checksum")?;
+    reader.finish()?;
+
+    if checksum(&bytes[..checksum_offset]) != expected_checksum {
+        return Err(SerializationErr::new(
+            ErrorCode::InvalidChecksum,
+            field_path,
+            format!(
+                "Provided share for '{}' has an invalid checksum. It was corrupted or entered incorrectly.",
+                field_path
+            ),
+        ));
+    }
+    let value = Fr256::from_bytes(&value_bytes);
+    if threshold < 2 || index == 0 || value.to_bytes() != value_bytes {
+        return Err(SerializationErr::new(
+            ErrorCode::InvalidEncoding,
+            field_path,
+            format!(
+                "Provided share for '{}' has an invalid threshold, index or value.",
+                field_path
+            ),
+        ));
+    }
+    Ok(Share {
+        threshold,
+        index,
+        public_key,
+        value,
+    })
+}
+
+/// Recover the secret from the provided shares. Every share must be from the same split and have a distinct index, and
+/// there must be at least `threshold` of them. Returns the secret and the public key the shares were created for, which
+/// the caller should check the recovered secret against.
+pub fn combine_shares(
+    shares: &[Share],
+    field_path: &str,
+) -> Result<([u8; 32], [u8; 64]), SerializationErr> {
+    let first = &shares[0];
+    for (i, share) in shares.iter().enumerate().skip(1) {
+        let share_path = format!("{}[{}]", field_path, i);
+        if share.public_key != first.public_key || share.threshold != first.threshold {
+            return Err(SerializationErr::new(
+                ErrorCode::PublicKeyMismatch,
+                &share_path,
+                format!(
+                    "Provided share for '{}' is from a different split than '{}[0]'.",
+                    share_path, field_path
+                ),
+            ));
+        }
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(SerializationErr::new(
+                ErrorCode::InvalidEncoding,
+                &share_path,
+                format!(
+                    "Provided share for '{}' has the same index as an earlier share.",
+                    share_path
+                ),
+            ));
+        }
+    }
+    if shares.len() < first.threshold as usize {
+        return Err(SerializationErr::new(
+            ErrorCode::NotEnoughShares,
+            field_path,
+            format!(
+                "Provided value for '{}' must have at least {} shares. Instead got {} shares.",
+                field_path,
+                first.threshold,
+                shares.len()
+            ),
+        ));
+    }
+
+    // Lagrange interpolation at 0 using the first `threshold` shares.
+    let shares = &shares[..first.threshold as usize];
+    let secret = shares.iter().fold(Fr256::zero(), |acc, share| {
+        let x_i = Fr256::from(share.index);
+        let basis = shares
+            .iter()
+            .filter(|other| other.index != share.index)
+            .fold(Fr256::one(), |basis, other| {
+                let x_j = Fr256::from(other.index);
+                basis * x_j * (x_j - x_i).inv()
+            });
+        acc + share.value * basis
+    });
+    Ok((secret.to_bytes(), first.public_key))
+}
+
+/// Convert a threshold or share count argument into a u8. Throws if it isn't an integer from 2 to 255.
+pub fn js_value_to_share_count<'a, T: Context<'a>>(
+    cx: &mut T,
+    value: Handle<'a, JsValue>,
+    field_path: &str,
+) -> NeonResult<u8> {
+    let number: Handle<JsNumber> = util::downcast_field(cx, value, field_path, "number")?;
+    let number = number.value(cx);
+    if number.fract() != 0.0 || !(2.0..=255.0).contains(&number) {
+        return util::throw_error(
+            cx,
+            ErrorKind::RangeError,
+            ErrorCode::InputWrongSize,
+            Some(field_path),
+            &format!(
+                "Provided value for '{}' must be an integer from 2 to 255. Instead got {}.",
+                field_path, number
+            ),
+        );
+    }
+    Ok(number as u8)
+}
@@ -0,0 +1,304 @@
+use crate::aes;
+use crate::util::{self, ErrorCode, ErrorKind};
+use aes_gcm::Aes256Gcm;
+use neon::{prelude::*, types::JsBuffer};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use recrypt::api::DerivedSymmetricKey;
+use std::cell::RefCell;
+
+// Chunked AES-256-GCM for payloads which are too large to encrypt in one shot, keyed by the symmetric key derived from a
+// recrypt Plaintext. An encrypted stream, version 1, is laid out as:
+//
+//   offset  size  field
+//   0       1     version (1)
+//   1       7     nonce prefix, random per stream
+//   8       ...   segments
+//
+// The data is split into 64KB chunks and each chunk is encrypted into a segment of the chunk followed by its 16 byte
+// GCM tag. Every segment is full size except the last one, which holds the remaining 0 to 64KB of data. The nonce for a
+// segment is the nonce prefix, followed by the 4 byte big endian index of the segment, followed by a byte which is 1 for
+// the last segment and 0 otherwise. Reordered segments fail to decrypt because their index is part of the nonce, and a
+// truncated stream fails to decrypt because its last segment wasn't encrypted with the last segment flag set.
+
+pub const STREAM_VERSION: u8 = 1;
+pub const CHUNK_LEN: usize = 64 * 1024;
+const NONCE_PREFIX_LEN: usize = 7;
+const HEADER_LEN: usize = 1 + NONCE_PREFIX_LEN;
+const SEGMENT_LEN: usize = CHUNK_LEN + aes::TAG_LEN;
+
+#[derive(Debug)]
+pub enum StreamErr {
+    /// `update` or `final` was called after `final` or after an earlier failure.
+    Finished,
+    /// The stream header has a version we don't know how to read.
+    UnsupportedVersion(u8),
+    /// The stream ended before its header or last segment.
+    Truncated,
+    /// A segment failed to authenticate, so it was modified, reordered or encrypted with a different key.
+    AuthenticationFailed(u32),
+    /// The stream has more segments than can be numbered.
+    TooLong,
+}
+
+/// Unwrap the result of a stream operation, throwing a JS error with a matching code on failure.
+pub fn stream_result_or_throw<'a, T: Context<'a>, R>(
+    cx: &mut T,
+    result: Result<R, StreamErr>,
+) -> NeonResult<R> {
+    let (kind, code, message) = match result {
+        Ok(value) => return Ok(value),
+        Err(StreamErr::Finished) => (
+            ErrorKind::Error,
+            ErrorCode::StreamFinished,
+            "The stream has already been finished.".to_string(),
+        ),
+        Err(StreamErr::UnsupportedVersion(version)) => (
+            ErrorKind::Error,
+            ErrorCode::UnsupportedVersion,
+            format!(
+                "Encrypted stream has unsupported version {}. Only version {} is supported.",
+                version, STREAM_VERSION
+            ),
+        ),
+        Err(StreamErr::Truncated) => (
+            ErrorKind::Error,
+            ErrorCode::InvalidCiphertext,
+            "Encrypted stream was truncated.".to_string(),
+        ),
+        Err(StreamErr::AuthenticationFailed(index)) => (
+            ErrorKind::Error,
+            ErrorCode::InvalidCiphertext,
+            format!(
+                "Segment {} of the encrypted stream failed to decrypt. The stream was modified, reordered or truncated, or was encrypted with a different key.",
+                index
+            ),
+        ),
+        Err(StreamErr::TooLong) => (
+            ErrorKind::RangeError,
+            ErrorCode::InputWrongSize,
+            "The stream is too long to encrypt.".to_string(),
+        ),
+    };
+    util::throw_error(cx, kind, code, None, &message)
+}
+
+fn segment_nonce(
+    nonce_prefix: &[u8; NONCE_PREFIX_LEN],
+    index: u32,
+    last: bool,
+) -> [u8; aes::NONCE_LEN] {
+    let mut nonce = [0u8; aes::NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(nonce_prefix);
+    nonce[NONCE_PREFIX_LEN..NONCE_PREFIX_LEN + 4].copy_from_slice(&index.to_be_bytes());
+    nonce[aes::NONCE_LEN - 1] = last as u8;
+    nonce
+}
+
+/// Encrypts data in the chunked stream format as it's provided.
+pub struct StreamEncryptor {
+    cipher: Aes256Gcm,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    index: u32,
+    // Data which hasn't been encrypted yet. A full chunk is held back until more data arrives, since it might be last.
+    pending: Vec<u8>,
+    header_written: bool,
+    finished: bool,
+}
+
+impl StreamEncryptor {
+    pub fn new(key: &DerivedSymmetricKey) -> StreamEncryptor {
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        OsRng.fill_bytes(&mut nonce_prefix);
+        StreamEncryptor {
+            cipher: aes::cipher(key.bytes()),
+            nonce_prefix,
+            index: 0,
+            pending: Vec::new(),
+            header_written: false,
+            finished: false,
+        }
+    }
+
+    fn header(&mut self, output: &mut Vec<u8>) {
+        if !self.header_written {
+            output.push(STREAM_VERSION);
+            output.extend_from_slice(&self.nonce_prefix);
+            self.header_written = true;
+        }
+    }
+
+    fn encrypt_segment(
+        &mut self,
+        chunk: &[u8],
+        last: bool,
+        output: &mut Vec<u8>,
+    ) -> Result<(), StreamErr> {
+        let nonce = segment_nonce(&self.nonce_prefix, self.index, last);
+        output.extend_from_slice(&aes::encrypt_with_nonce(&self.cipher, nonce, chunk, &[]));
+        self.index = self.index.checked_add(1).ok_or(StreamErr::TooLong)?;
+        Ok(())
+    }
+
+    /// Add data to the stream. Returns the encrypted bytes which are ready, which may be empty.
+    pub fn update(&mut self, data: &[u8]) -> Result<Vec<u8>, StreamErr> {
+        if self.finished {
+            return Err(StreamErr::Finished);
+        }
+        let mut output = Vec::new();
+        self.header(&mut output);
+        self.pending.extend_from_slice(data);
+        let full_chunks = (self.pending.len().saturating_sub(1)) / CHUNK_LEN;
+        let ready: Vec<u8> = self.pending.drain(..full_chunks * CHUNK_LEN).collect();
+        for chunk in ready.chunks(CHUNK_LEN) {
+            self.encrypt_segment(chunk, false, &mut output)?;
+        }
+        Ok(output)
+    }
+
+    /// Encrypt the remaining data as the last segment. Returns the rest of the encrypted stream.
+    pub fn finish(&mut self) -> Result<Vec<u8>, StreamErr> {
+        if self.finished {
+            return Err(StreamErr::Finished);
+        }
+        self.finished = true;
+        let mut output = Vec::new();
+        self.header(&mut output);
+        let last_chunk = std::mem::take(&mut self.pending);
+        self.encrypt_segment(&last_chunk, true, &mut output)?;
+        Ok(output)
+    }
+}
+
+/// Decrypts a stream in the chunked stream format as it's provided.
+pub struct StreamDecryptor {
+    cipher: Aes256Gcm,
+    nonce_prefix: Option<[u8; NONCE_PREFIX_LEN]>,
+    index: u32,
+    // Encrypted bytes which haven't been decrypted yet. A full segment is held back until more bytes arrive, since it
+    // might be last.
+    pending: Vec<u8>,
+    finished: bool,
+}
+
+impl StreamDecryptor {
+    pub fn new(key: &DerivedSymmetricKey) -> StreamDecryptor {
+        StreamDecryptor {
+            cipher: aes::cipher(key.bytes()),
+            nonce_prefix: None,
+            index: 0,
+            pending: Vec::new(),
+            finished: false,
+        }
+    }
+
+    fn read_header(&mut self) -> Result<Option<[u8; NONCE_PREFIX_LEN]>, StreamErr> {
+        if self.nonce_prefix.is_none() && self.pending.len() >= HEADER_LEN {
+            let header: Vec<u8> = self.pending.drain(..HEADER_LEN).collect();
+            if header[0] != STREAM_VERSION {
+                return Err(StreamErr::UnsupportedVersion(header[0]));
+            }
+            let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+            nonce_prefix.copy_from_slice(&header[1..]);
+            self.nonce_prefix = Some(nonce_prefix);
+        }
+        Ok(self.nonce_prefix)
+    }
+
+    fn decrypt_segment(
+        &mut self,
+        nonce_prefix: &[u8; NONCE_PREFIX_LEN],
+        segment: &[u8],
+        last: bool,
+        output: &mut Vec<u8>,
+    ) -> Result<(), StreamErr> {
+        let nonce = segment_nonce(nonce_prefix, self.index, last);
+        let chunk = aes::decrypt_with_nonce(&self.cipher, nonce, segment, &[])
+            .map_err(|_| StreamErr::AuthenticationFailed(self.index))?;
+        output.extend_from_slice(&chunk);
+        self.index = self.index.checked_add(1).ok_or(StreamErr::TooLong)?;
+        Ok(())
+    }
+
+    /// Add encrypted bytes to the stream. Returns the decrypted data which is ready, which may be empty. Any failure
+    /// ends the stream.
+    pub fn update(&mut self, encrypted: &[u8]) -> Result<Vec<u8>, StreamErr> {
+        if self.finished {
+            return Err(StreamErr::Finished);
+        }
+        self.pending.extend_from_slice(encrypted);
+        let result = self.decrypt_ready();
+        self.finished = result.is_err();
+        result
+    }
+
+    fn decrypt_ready(&mut self) -> Result<Vec<u8>, StreamErr> {
+        let mut output = Vec::new();
+        if let Some(nonce_prefix) = self.read_header()? {
+            let full_segments = (self.pending.len().saturating_sub(1)) / SEGMENT_LEN;
+            let ready: Vec<u8> = self.pending.drain(..full_segments * SEGMENT_LEN).collect();
+            for segment in ready.chunks(SEGMENT_LEN) {
+                self.decrypt_segment(&nonce_prefix, segment, false, &mut output)?;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Decrypt the last segment. Fails if the stream was truncated. Returns the rest of the decrypted data.
+    pub fn finish(&mut self) -> Result<Vec<u8>, StreamErr> {
+        if self.finished {
+            return Err(StreamErr::Finished);
+        }
+        self.finished = true;
+        let nonce_prefix = self.read_header()?.ok_or(StreamErr::Truncated)?;
+        if self.pending.len() < aes::TAG_LEN {
+            return Err(StreamErr::Truncated);
+        }
+        let last_segment = std::mem::take(&mut self.pending);
+        let mut output = Vec::new();
+        self.decrypt_segment(&nonce_prefix, &last_segment, true, &mut output)?;
+        Ok(output)
+    }
+}
+
+pub struct StreamEncryptorHandle(pub RefCell<StreamEncryptor>);
+impl Finalize for StreamEncryptorHandle {}
+
+pub struct StreamDecryptorHandle(pub RefCell<StreamDecryptor>);
+impl Finalize for StreamDecryptorHandle {}
+
+pub fn stream_encryptor_update(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = cx.argument::<JsBox<StreamEncryptorHandle>>(0)?;
+    let chunk_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let chunk = util::buffer_to_variable_bytes(&cx, chunk_buffer);
+
+    let encrypted = handle.0.borrow_mut().update(&chunk);
+    let encrypted = stream_result_or_throw(&mut cx, encrypted)?;
+    util::bytes_to_buffer(&mut cx, &encrypted)
+}
+
+pub fn stream_encryptor_final(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = cx.argument::<JsBox<StreamEncryptorHandle>>(0)?;
+
+    let encrypted = handle.0.borrow_mut().finish();
+    let encrypted = stream_result_or_throw(&mut cx, encrypted)?;
+    util::bytes_to_buffer(&mut cx, &encrypted)
+}
+
+pub fn stream_decryptor_update(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = cx.argument::<JsBox<StreamDecryptorHandle>>(0)?;
+    let chunk_buffer: Handle<JsBuffer> = cx.argument::<JsBuffer>(1)?;
+    let chunk = util::buffer_to_variable_bytes(&cx, chunk_buffer);
+
+    let decrypted = handle.0.borrow_mut().update(&chunk);
+    let decrypted = stream_result_or_throw(&mut cx, decrypted)?;
+    util::bytes_to_buffer(&mut cx, &decrypted)
+}
+
+pub fn stream_decryptor_final(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = cx.argument::<JsBox<StreamDecryptorHandle>>(0)?;
+
+    let decrypted = handle.0.borrow_mut().finish();
+    let decrypted = stream_result_or_throw(&mut cx, decrypted)?;
+    util::bytes_to_buffer(&mut cx, &decrypted)
+}
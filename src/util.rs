@@ -1,39 +1,407 @@
+use crate::compression;
 use neon::types::buffer::TypedArray;
 use neon::{prelude::*, types::JsBuffer};
 use recrypt::api::{
     AuthHash, Ed25519Signature, EncryptedMessage, EncryptedTempKey, EncryptedValue, HashedValue,
-    Plaintext, PrivateKey, PublicKey, PublicSigningKey, SchnorrSignature, TransformBlock,
-    TransformKey,
+    Plaintext, PrivateKey, PublicKey, PublicSigningKey, RecryptErr, SchnorrSignature,
+    SigningKeypair, TransformBlock, TransformKey,
 };
 use recrypt::nonemptyvec::NonEmptyVec;
 
-/// Create an `$n` byte fixed u8 array given the provided JsBuffer handle. Throws an error if the provided Buffer
+/// Machine-readable codes which are set as the `code` property on every error we throw back into JS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// A required object field was `undefined`.
+    MissingField,
+    /// A field or argument was present but was not of the expected JS type.
+    InvalidFieldType,
+    /// A Buffer field or argument was not of the required length.
+    InvalidBufferLength,
+    /// The bytes provided for a public key did not represent a valid point on the curve.
+    InvalidPublicKey,
+    /// The bytes provided for a hashed temp key did not represent a valid point on the curve.
+    InvalidHashedValue,
+    /// The bytes provided for an Ed25519 signing key pair were not a valid key pair.
+    InvalidSigningKeyPair,
+    /// An array which must contain at least one element was empty.
+    EmptyArray,
+    /// Decryption failed, most likely because the wrong PrivateKey was used.
+    DecryptFailed,
+    /// The Ed25519 signature over an encrypted value didn't verify, meaning it has been tampered with.
+    InvalidEncryptedMessageSignature,
+    /// The signature over a transform key didn't verify, meaning it has been tampered with.
+    InvalidTransformKey,
+    /// Recrypt was given a value which was not the size it requires.
+    InputWrongSize,
+    /// Recrypt couldn't decode the provided bytes into the type it expected.
+    DecodeFailure,
+    /// Serialized bytes were written with a version of the layout which we don't know how to read.
+    UnsupportedVersion,
+    /// Serialized bytes were the right length but didn't hold a valid value, e.g. an unknown variant tag.
+    InvalidEncoding,
+    /// AES-GCM authentication of encrypted data failed, meaning it was modified or the wrong key or AAD was used.
+    InvalidCiphertext,
+    /// A streaming encryptor or decryptor was used after `final` or after it failed.
+    StreamFinished,
+    /// A shared document has no encrypted DEK for the requested recipient id.
+    UnknownRecipient,
+    /// A mnemonic didn't have the right number of words or contained a word which isn't in the wordlist.
+    InvalidMnemonic,
+    /// The checksum embedded in an encoded value didn't match, most likely because it was entered incorrectly.
+    InvalidChecksum,
+    /// A private key didn't produce the public key it was expected to.
+    PublicKeyMismatch,
+    /// Fewer secret shares were provided than the threshold they were split with.
+    NotEnoughShares,
+    /// A value was signed with a different Ed25519 key than the one it was expected to be signed with.
+    PublicSigningKeyMismatch,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::MissingField => "MissingField",
+            ErrorCode::InvalidFieldType => "InvalidFieldType",
+            ErrorCode::InvalidBufferLength => "InvalidBufferLength",
+            ErrorCode::InvalidPublicKey => "InvalidPublicKey",
+            ErrorCode::InvalidHashedValue => "InvalidHashedValue",
+            ErrorCode::InvalidSigningKeyPair => "InvalidSigningKeyPair",
+            ErrorCode::EmptyArray => "EmptyArray",
+            ErrorCode::DecryptFailed => "DecryptFailed",
+            ErrorCode::InvalidEncryptedMessageSignature => "InvalidEncryptedMessageSignature",
+            ErrorCode::InvalidTransformKey => "InvalidTransformKey",
+            ErrorCode::InputWrongSize => "InputWrongSize",
+            ErrorCode::DecodeFailure => "DecodeFailure",
+            ErrorCode::UnsupportedVersion => "UnsupportedVersion",
+            ErrorCode::InvalidEncoding => "InvalidEncoding",
+            ErrorCode::InvalidCiphertext => "InvalidCiphertext",
+            ErrorCode::StreamFinished => "StreamFinished",
+            ErrorCode::UnknownRecipient => "UnknownRecipient",
+            ErrorCode::InvalidMnemonic => "InvalidMnemonic",
+            ErrorCode::InvalidChecksum => "InvalidChecksum",
+            ErrorCode::PublicKeyMismatch => "PublicKeyMismatch",
+            ErrorCode::NotEnoughShares => "NotEnoughShares",
+            ErrorCode::PublicSigningKeyMismatch => "PublicSigningKeyMismatch",
+        }
+    }
+}
+
+/// The JS error constructors we throw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Error,
+    TypeError,
+    RangeError,
+}
+
+/// Create a JS error of the provided kind with the provided message. The error will have its `code` property set to the
+/// provided code and, if provided, a `field` property set to the full path of the field which failed to convert (e.g.
+/// `encryptedValue.transformBlocks[1].publicKey.y`).
+pub fn create_error<'a, T: Context<'a>>(
+    cx: &mut T,
+    kind: ErrorKind,
+    code: ErrorCode,
+    field_path: Option<&str>,
+    message: &str,
+) -> JsResult<'a, JsError> {
+    let error = match kind {
+        ErrorKind::Error => JsError::error(cx, message)?,
+        ErrorKind::TypeError => JsError::type_error(cx, message)?,
+        ErrorKind::RangeError => JsError::range_error(cx, message)?,
+    };
+    let code = cx.string(code.as_str());
+    error.set(cx, "code", code)?;
+    if let Some(path) = field_path {
+        let field = cx.string(path);
+        error.set(cx, "field", field)?;
+    }
+    Ok(error)
+}
+
+/// Throw a JS error created via `create_error`.
+pub fn throw_error<'a, T: Context<'a>, R>(
+    cx: &mut T,
+    kind: ErrorKind,
+    code: ErrorCode,
+    field_path: Option<&str>,
+    message: &str,
+) -> NeonResult<R> {
+    let error = create_error(cx, kind, code, field_path, message)?;
+    cx.throw(error)
+}
+
+/// Run the argument conversion for a `*_async` function. If it throws, the error is caught and handed back as a rejected
+/// Promise instead, so async functions only ever fail through the Promise they return.
+pub fn parse_or_reject<'a, T, F>(
+    cx: &mut FunctionContext<'a>,
+    parse: F,
+) -> Result<T, Handle<'a, JsPromise>>
+where
+    F: FnOnce(&mut FunctionContext<'a>) -> NeonResult<T>,
+{
+    cx.try_catch(parse).map_err(|error| {
+        let (deferred, promise) = cx.promise();
+        deferred.reject(cx, error);
+        promise
+    })
+}
+
+/// Map a RecryptErr onto the JS error kind and code we throw for it.
+pub fn recrypt_err_kind_and_code(err: &RecryptErr) -> (ErrorKind, ErrorCode) {
+    match err {
+        RecryptErr::DecryptFailed(_) => (ErrorKind::Error, ErrorCode::DecryptFailed),
+        RecryptErr::InvalidEncryptedMessageSignature(_) => (
+            ErrorKind::Error,
+            ErrorCode::InvalidEncryptedMessageSignature,
+        ),
+        RecryptErr::InvalidPublicKey(_) => (ErrorKind::Error, ErrorCode::InvalidPublicKey),
+        RecryptErr::InvalidTransformKey(_) => (ErrorKind::Error, ErrorCode::InvalidTransformKey),
+        RecryptErr::InputWrongSize(_, _) => (ErrorKind::RangeError, ErrorCode::InputWrongSize),
+        RecryptErr::DecodeFailure(_) => (ErrorKind::Error, ErrorCode::DecodeFailure),
+    }
+}
+
+/// Create a JS error whose `code` matches the RecryptErr variant.
+pub fn recrypt_err_to_js_error<'a, T: Context<'a>>(
+    cx: &mut T,
+    err: &RecryptErr,
+) -> JsResult<'a, JsError> {
+    let (kind, code) = recrypt_err_kind_and_code(err);
+    create_error(cx, kind, code, None, &err.to_string())
+}
+
+/// Unwrap the result of a recrypt operation. On failure throws a JS error whose `code` matches the RecryptErr variant,
+/// so callers can tell a tampered value apart from a malformed key.
+pub fn recrypt_result_or_throw<'a, T: Context<'a>, R>(
+    cx: &mut T,
+    result: Result<R, RecryptErr>,
+) -> NeonResult<R> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            let error = recrypt_err_to_js_error(cx, &e)?;
+            cx.throw(error)
+        }
+    }
+}
+
+/// Get the `key` property off of the provided object and downcast it to `V`. Throws a TypeError if the property is
+/// missing or isn't of the expected type. `field_path` is the path to `object` and is used to build the error message.
+pub fn get_field<'a, V: Value, T: Context<'a>>(
+    cx: &mut T,
+    object: Handle<JsObject>,
+    field_path: &str,
+    key: &str,
+    expected_type: &str,
+) -> NeonResult<Handle<'a, V>> {
+    let value = object.get_value(cx, key)?;
+    let full_path = format!("{}.{}", field_path, key);
+    if value.is_a::<JsUndefined, _>(cx) {
+        return throw_error(
+            cx,
+            ErrorKind::TypeError,
+            ErrorCode::MissingField,
+            Some(&full_path),
+            &format!("Missing required field '{}'.", full_path),
+        );
+    }
+    downcast_field(cx, value, &full_path, expected_type)
+}
+
+/// Downcast the provided value to `V`. Throws a TypeError which names the field if it isn't of the expected type.
+pub fn downcast_field<'a, V: Value, T: Context<'a>>(
+    cx: &mut T,
+    value: Handle<'a, JsValue>,
+    field_path: &str,
+    expected_type: &str,
+) -> NeonResult<Handle<'a, V>> {
+    match value.downcast::<V, _>(cx) {
+        Ok(v) => Ok(v),
+        Err(_) => throw_error(
+            cx,
+            ErrorKind::TypeError,
+            ErrorCode::InvalidFieldType,
+            Some(field_path),
+            &format!(
+                "Provided value for '{}' must be a {}.",
+                field_path, expected_type
+            ),
+        ),
+    }
+}
+
+/// Downcast an optional argument to `V`. Returns None if the argument was omitted or is `undefined` or `null`.
+pub fn downcast_optional_argument<'a, V: Value, T: Context<'a>>(
+    cx: &mut T,
+    value: Option<Handle<'a, JsValue>>,
+    field_path: &str,
+    expected_type: &str,
+) -> NeonResult<Option<Handle<'a, V>>> {
+    match value {
+        Some(value) if !value.is_a::<JsUndefined, _>(cx) && !value.is_a::<JsNull, _>(cx) => {
+            Ok(Some(downcast_field(cx, value, field_path, expected_type)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Get the own enumerable properties of an object used as a map from string keys to values, in property order. Calls
+/// `Object.keys` since listing property names natively needs a newer N-API version than we target.
+pub fn js_object_entries<'a, T: Context<'a>>(
+    cx: &mut T,
+    object: Handle<'a, JsObject>,
+) -> NeonResult<Vec<(String, Handle<'a, JsValue>)>> {
+    let object_class: Handle<JsFunction> = cx.global().get(cx, "Object")?;
+    let object_keys: Handle<JsFunction> = object_class.get(cx, "keys")?;
+    let keys: Handle<JsArray> = object_keys
+        .call(cx, object_class, [object.upcast()])?
+        .downcast_or_throw(cx)?;
+    let keys: Vec<Handle<JsValue>> = keys.to_vec(cx)?;
+    let mut entries = Vec::with_capacity(keys.len());
+    for key in keys {
+        let key = key.to_string(cx)?.value(cx);
+        let value = object.get_value(cx, key.as_str())?;
+        entries.push((key, value));
+    }
+    Ok(entries)
+}
+
+/// Create an empty object with a null prototype, for objects keyed by caller provided ids. Setting a key such as
+/// `__proto__` on a plain `{}` would replace its prototype instead of adding an entry.
+pub fn empty_dictionary_object<'a, T: Context<'a>>(cx: &mut T) -> JsResult<'a, JsObject> {
+    let object_class: Handle<JsFunction> = cx.global().get(cx, "Object")?;
+    let object_create: Handle<JsFunction> = object_class.get(cx, "create")?;
+    let null = cx.null();
+    object_create
+        .call(cx, object_class, [null.upcast()])?
+        .downcast_or_throw(cx)
+}
+
+/// Convert each element of the provided array into an object and then pass it to `convert`. Anything thrown during the
+/// conversion of an element is caught and kept in that element's slot so that one bad element doesn't fail the whole
+/// batch. Each element is converted with a field path of `field_path[i]`.
+pub fn js_array_to_batch<'a, T, R, F>(
+    cx: &mut T,
+    js_array: Handle<JsArray>,
+    field_path: &str,
+    convert: F,
+) -> NeonResult<Vec<Result<R, Handle<'a, JsValue>>>>
+where
+    T: Context<'a>,
+    F: Fn(&mut T, Handle<'a, JsObject>, &str) -> NeonResult<R>,
+{
+    let values: Vec<Handle<JsValue>> = js_array.to_vec(cx)?;
+    let mut batch = Vec::with_capacity(values.len());
+    for (i, value) in values.into_iter().enumerate() {
+        let element_path = format!("{}[{}]", field_path, i);
+        batch.push(cx.try_catch(|cx| {
+            let object = downcast_field::<JsObject, _>(cx, value, &element_path, "object")?;
+            convert(cx, object, &element_path)
+        }));
+    }
+    Ok(batch)
+}
+
+/// Convert the results of a batch operation into a JS array. Each entry is either the successful result passed through
+/// `convert` or the error which occurred for the element at the same index.
+pub fn batch_results_to_js_array<'a, T, R, F>(
+    cx: &mut T,
+    results: Vec<Result<Result<R, RecryptErr>, Handle<'a, JsValue>>>,
+    convert: F,
+) -> JsResult<'a, JsArray>
+where
+    T: Context<'a>,
+    F: Fn(&mut T, R) -> JsResult<'a, JsValue>,
+{
+    let results_array = JsArray::new(cx, results.len() as u32);
+    for (i, result) in results.into_iter().enumerate() {
+        let value = match result {
+            Ok(Ok(value)) => convert(cx, value)?,
+            Ok(Err(e)) => recrypt_err_to_js_error(cx, &e)?.upcast(),
+            Err(error) => error,
+        };
+        results_array.set(cx, i as u32, value)?;
+    }
+    Ok(results_array)
+}
+
+/// Read `key` from an optional options object argument and downcast it to `V`. Returns None if the options object or
+/// the key is missing. Throws a TypeError which names the option if it isn't of the expected type.
+pub fn get_option<'a, V: Value, T: Context<'a>>(
+    cx: &mut T,
+    options: Option<Handle<'a, JsValue>>,
+    key: &str,
+    expected_type: &str,
+) -> NeonResult<Option<Handle<'a, V>>> {
+    let options = match options {
+        Some(options) if !options.is_a::<JsUndefined, _>(cx) && !options.is_a::<JsNull, _>(cx) => {
+            downcast_field::<JsObject, _>(cx, options, "options", "object")?
+        }
+        _ => return Ok(None),
+    };
+    let value = options.get_value(cx, key)?;
+    if value.is_a::<JsUndefined, _>(cx) {
+        return Ok(None);
+    }
+    let value = downcast_field::<V, _>(cx, value, &format!("options.{}", key), expected_type)?;
+    Ok(Some(value))
+}
+
+/// Read the boolean `key` from an optional options object argument. Returns false if the options object or the key is
+/// missing.
+pub fn get_bool_option<'a, T: Context<'a>>(
+    cx: &mut T,
+    options: Option<Handle<'a, JsValue>>,
+    key: &str,
+) -> NeonResult<bool> {
+    Ok(
+        match get_option::<JsBoolean, _>(cx, options, key, "boolean")? {
+            Some(value) => value.value(cx),
+            None => false,
+        },
+    )
+}
+
+/// Create an `$n` byte fixed u8 array given the provided JsBuffer handle. Throws a RangeError if the provided Buffer
 /// is not of the required length.
 macro_rules! buffer_to_fixed_bytes { ($($fn_name: ident, $n: expr); *) => {
-    $(pub fn $fn_name<'a, T>(cx: &T, buffer: Handle<JsBuffer>, field_name: &str) -> [u8; $n]
+    $(pub fn $fn_name<'a, T>(cx: &mut T, buffer: Handle<JsBuffer>, field_name: &str) -> NeonResult<[u8; $n]>
         where T: Context<'a>{
         let slice = buffer.as_slice(cx);
         if slice.len() != $n {
-            panic!("Provided Buffer for '{}' is not of expected size of {} bytes. Instead got {} bytes.", field_name, $n, slice.len());
+            let message = format!("Provided Buffer for '{}' is not of expected size of {} bytes. Instead got {} bytes.", field_name, $n, slice.len());
+            return throw_error(cx, ErrorKind::RangeError, ErrorCode::InvalidBufferLength, Some(field_name), &message);
         }
         let mut result: [u8;$n] = [0;$n];
         result.clone_from_slice(slice);
-        result
+        Ok(result)
     })+
 }}
 
-// Create the various methods we need to convert buffers into fixed length bytes
-buffer_to_fixed_bytes! {buffer_to_fixed_32_bytes, 32; buffer_to_fixed_64_bytes, 64; buffer_to_fixed_128_bytes, 128; buffer_to_fixed_384_bytes, 384}
-
-/// Create a macro for converting JsBuffers to different types of signature objects which all have the same size.
-macro_rules! buffer_to_signature { ($($fn_name: ident, $sig_type: expr, $ret_type: ty); *) => {
-    $(pub fn $fn_name<'a, T: Context<'a>>(cx: &T, buffer: Handle<JsBuffer>) -> $ret_type {
-        $sig_type(buffer_to_fixed_64_bytes(cx, buffer, "signature"))
-    })+
-}}
+// Create the various methods we need to convert buffers into fixed length bytes for both the 256-bit and 480-bit curves
+buffer_to_fixed_bytes! {
+    buffer_to_fixed_32_bytes, 32;
+    buffer_to_fixed_33_bytes, 33;
+    buffer_to_fixed_60_bytes, 60;
+    buffer_to_fixed_61_bytes, 61;
+    buffer_to_fixed_64_bytes, 64;
+    buffer_to_fixed_120_bytes, 120;
+    buffer_to_fixed_128_bytes, 128;
+    buffer_to_fixed_240_bytes, 240;
+    buffer_to_fixed_384_bytes, 384;
+    buffer_to_fixed_720_bytes, 720
+}
 
-// Create two methods from the macro for Schnorr and ED25519 signatures
-buffer_to_signature! {buffer_to_schnorr_signature, SchnorrSignature::new, SchnorrSignature; buffer_to_ed25519_signature, Ed25519Signature::new, Ed25519Signature}
+/// Convert a JsBuffer handle into an Ed25519Signature.
+pub fn buffer_to_ed25519_signature<'a, T: Context<'a>>(
+    cx: &mut T,
+    buffer: Handle<JsBuffer>,
+    field_name: &str,
+) -> NeonResult<Ed25519Signature> {
+    Ok(Ed25519Signature::new(buffer_to_fixed_64_bytes(
+        cx, buffer, field_name,
+    )?))
+}
 
 /// Convert a JsBuffer handle of variable size into a vector
 pub fn buffer_to_variable_bytes<'a, T: Context<'a>>(cx: &T, buffer: Handle<JsBuffer>) -> Vec<u8> {
@@ -45,288 +413,493 @@ pub fn bytes_to_buffer<'a, T: Context<'a>>(
     cx: &mut T,
     data: &[u8],
 ) -> NeonResult<Handle<'a, JsBuffer>> {
-    let mut buffer: Handle<JsBuffer> = cx.buffer(data.len() as usize)?;
+    let mut buffer: Handle<JsBuffer> = cx.buffer(data.len())?;
     buffer.as_mut_slice(cx).copy_from_slice(data);
     Ok(buffer)
 }
 
-/// Convert a JsBuffer handle into a PrivateKey
-pub fn buffer_to_private_key<'a, T: Context<'a>>(cx: &T, buffer: Handle<JsBuffer>) -> PrivateKey {
-    PrivateKey::new(buffer_to_fixed_32_bytes(cx, buffer, "privateKey"))
+/// Convert a JsBuffer handle into an Ed25519 SigningKeypair. Throws if the public key half of the bytes doesn't match
+/// the private key half.
+pub fn buffer_to_signing_key_pair<'a, T: Context<'a>>(
+    cx: &mut T,
+    buffer: Handle<JsBuffer>,
+    field_name: &str,
+) -> NeonResult<SigningKeypair> {
+    let bytes = buffer_to_fixed_64_bytes(cx, buffer, field_name)?;
+    match SigningKeypair::from_bytes(&bytes) {
+        Ok(signing_key_pair) => Ok(signing_key_pair),
+        Err(e) => throw_error(
+            cx,
+            ErrorKind::Error,
+            ErrorCode::InvalidSigningKeyPair,
+            Some(field_name),
+            &format!("Provided value for '{}' is invalid. {}", field_name, e),
+        ),
+    }
 }
 
-/// Convert a JsBuffer handle to a Plaintext object.
-pub fn buffer_to_plaintext<'a, T: Context<'a>>(cx: &T, buffer: Handle<JsBuffer>) -> Plaintext {
-    Plaintext::new(buffer_to_fixed_384_bytes(cx, buffer, "plaintext"))
-}
+/// Generate the converters between JS values and the recrypt types which differ between the 256-bit and 480-bit
+/// curves. The converters are written once and expanded for each curve with the fixed byte conversion functions for
+/// that curve's sizes, so the two APIs can't drift apart. The recrypt types are resolved where the macro is expanded.
+macro_rules! curve_converters {
+    (
+        private_key: $private_key_bytes: ident,
+        coordinate: $coordinate_bytes: ident,
+        fp12: $fp12_bytes: ident,
+        hashed_value: $hashed_value_bytes: ident,
+        schnorr_signature: $schnorr_signature_bytes: ident,
+        compressed_public_key: $compressed_public_key_bytes: ident,
+        decompress_public_key: $decompress_public_key: path $(,)?
+    ) => {
+        /// Convert a JsBuffer handle into a SchnorrSignature
+        pub fn buffer_to_schnorr_signature<'a, T: Context<'a>>(
+            cx: &mut T,
+            buffer: Handle<JsBuffer>,
+            field_name: &str,
+        ) -> NeonResult<SchnorrSignature> {
+            Ok(SchnorrSignature::new($schnorr_signature_bytes(
+                cx, buffer, field_name,
+            )?))
+        }
 
-/// Convert a JsObject with x/y Buffers into a PublicKey
-pub fn js_object_to_public_key<'a, T: Context<'a>>(
-    cx: &mut T,
-    object: Handle<JsObject>,
-) -> PublicKey {
-    let x = object.get(cx, "x").unwrap();
-    let y = object.get(cx, "y").unwrap();
-
-    PublicKey::new((
-        buffer_to_fixed_32_bytes(cx, x, "publicKey.x"),
-        buffer_to_fixed_32_bytes(cx, y, "publicKey.y"),
-    ))
-    .unwrap()
-}
+        /// Convert a JsBuffer handle into a PrivateKey
+        pub fn buffer_to_private_key<'a, T: Context<'a>>(
+            cx: &mut T,
+            buffer: Handle<JsBuffer>,
+            field_name: &str,
+        ) -> NeonResult<PrivateKey> {
+            Ok(PrivateKey::new($private_key_bytes(cx, buffer, field_name)?))
+        }
 
-/// Convert a Recrypt PublicKey struct into a JsObject with x/y properties which are Buffers
-pub fn public_key_to_js_object<'a, T: Context<'a>>(
-    cx: &mut T,
-    public_key: &PublicKey,
-) -> NeonResult<Handle<'a, JsObject>> {
-    let public_key_obj: Handle<JsObject> = cx.empty_object();
-    let (x, y) = public_key.bytes_x_y();
-    let public_key_x_buffer = bytes_to_buffer(cx, x)?;
-    let public_key_y_buffer = bytes_to_buffer(cx, y)?;
-
-    public_key_obj.set(cx, "x", public_key_x_buffer)?;
-    public_key_obj.set(cx, "y", public_key_y_buffer)?;
-    Ok(public_key_obj)
-}
+        /// Convert a JsBuffer handle to a Plaintext object.
+        pub fn buffer_to_plaintext<'a, T: Context<'a>>(
+            cx: &mut T,
+            buffer: Handle<JsBuffer>,
+            field_name: &str,
+        ) -> NeonResult<Plaintext> {
+            Ok(Plaintext::new($fp12_bytes(cx, buffer, field_name)?))
+        }
 
-/// Convert a JsObject which represents a TransformKey into an internal recrypt TransformKey
-pub fn js_object_to_transform_key<'a, T: Context<'a>>(
-    cx: &mut T,
-    object: Handle<JsObject>,
-) -> TransformKey {
-    let encrypted_temp_key_buffer = object.get(cx, "encryptedTempKey").unwrap();
-    let ephemeral_public_key_obj = object.get(cx, "ephemeralPublicKey").unwrap();
-    let hashed_temp_key_buffer = object.get(cx, "hashedTempKey").unwrap();
-    let public_signing_key_buffer = object.get(cx, "publicSigningKey").unwrap();
-    let signature_buffer = object.get(cx, "signature").unwrap();
-    let to_public_key_obj = object.get(cx, "toPublicKey").unwrap();
-
-    TransformKey::new(
-        js_object_to_public_key(cx, ephemeral_public_key_obj),
-        js_object_to_public_key(cx, to_public_key_obj),
-        EncryptedTempKey::new(buffer_to_fixed_384_bytes(
-            cx,
-            encrypted_temp_key_buffer,
-            "encryptedTempKey",
-        )),
-        HashedValue::new(buffer_to_fixed_128_bytes(
-            cx,
-            hashed_temp_key_buffer,
-            "hashedTempKey",
-        ))
-        .unwrap(),
-        PublicSigningKey::new(buffer_to_fixed_32_bytes(
-            cx,
-            public_signing_key_buffer,
-            "publicSigningKey",
-        )),
-        buffer_to_ed25519_signature(cx, signature_buffer),
-    )
-}
+        /// Convert a JsObject with x/y Buffers into a PublicKey. The object may also be a Buffer holding the compressed
+        /// form of the public key, in which case the point is decompressed and validated.
+        pub fn js_object_to_public_key<'a, T: Context<'a>>(
+            cx: &mut T,
+            object: Handle<JsObject>,
+            field_path: &str,
+        ) -> NeonResult<PublicKey> {
+            if let Ok(compressed) = object.downcast::<JsBuffer, _>(cx) {
+                let compressed_bytes = $compressed_public_key_bytes(cx, compressed, field_path)?;
+                return match $decompress_public_key(&compressed_bytes) {
+                    Ok(public_key) => Ok(public_key),
+                    Err(e) => throw_error(
+                        cx,
+                        ErrorKind::Error,
+                        ErrorCode::InvalidPublicKey,
+                        Some(field_path),
+                        &format!("Provided value for '{}' is invalid. {}", field_path, e),
+                    ),
+                };
+            }
+            let x = get_field(cx, object, field_path, "x", "Buffer")?;
+            let y = get_field(cx, object, field_path, "y", "Buffer")?;
+            let x_bytes = $coordinate_bytes(cx, x, &format!("{}.x", field_path))?;
+            let y_bytes = $coordinate_bytes(cx, y, &format!("{}.y", field_path))?;
 
-/// Convert a Recrypt TransformKey into a JsObject with expected properties and bytes converted to Buffers
-pub fn transform_key_to_js_object<'a, T: Context<'a>>(
-    cx: &mut T,
-    transform_key: TransformKey,
-) -> NeonResult<Handle<'a, JsObject>> {
-    let transform_key_obj = cx.empty_object();
-    let to_public_key = public_key_to_js_object(cx, transform_key.to_public_key())?;
-    let ephemeral_public_key = public_key_to_js_object(cx, transform_key.ephemeral_public_key())?;
-    let encrypted_temp_key_buffer =
-        bytes_to_buffer(cx, transform_key.encrypted_temp_key().bytes())?;
-    let hashed_temp_key_buffer = bytes_to_buffer(cx, transform_key.hashed_temp_key().bytes())?;
-    let public_signing_key_buffer =
-        bytes_to_buffer(cx, transform_key.public_signing_key().bytes())?;
-    let signature_buffer: Handle<JsBuffer> =
-        bytes_to_buffer(cx, transform_key.signature().bytes())?;
-
-    transform_key_obj.set(cx, "toPublicKey", to_public_key)?;
-    transform_key_obj.set(cx, "ephemeralPublicKey", ephemeral_public_key)?;
-    transform_key_obj.set(cx, "encryptedTempKey", encrypted_temp_key_buffer)?;
-    transform_key_obj.set(cx, "hashedTempKey", hashed_temp_key_buffer)?;
-    transform_key_obj.set(cx, "publicSigningKey", public_signing_key_buffer)?;
-    transform_key_obj.set(cx, "signature", signature_buffer)?;
-    Ok(transform_key_obj)
-}
+            match PublicKey::new((x_bytes, y_bytes)) {
+                Ok(public_key) => Ok(public_key),
+                Err(e) => throw_error(
+                    cx,
+                    ErrorKind::Error,
+                    ErrorCode::InvalidPublicKey,
+                    Some(field_path),
+                    &format!("Provided value for '{}' is invalid. {}", field_path, e),
+                ),
+            }
+        }
 
-/// Convert an array of transform blocks into a non-empty vector of internal recrypt TransformBlock structs.
-pub fn js_object_to_transform_blocks<'a, T: Context<'a>>(
-    cx: &mut T,
-    js_array: Handle<JsArray>,
-) -> NonEmptyVec<TransformBlock> {
-    let transform_blocks: Vec<Handle<JsValue>> = js_array.to_vec(cx).unwrap();
-
-    let blocks: Vec<TransformBlock> = transform_blocks
-        .iter()
-        .map(|block| {
-            let block_obj = block.downcast::<JsObject, _>(cx).unwrap();
-            let public_key = block_obj.get(cx, "publicKey").unwrap();
-            let encrypted_temp_key = block_obj.get(cx, "encryptedTempKey").unwrap();
-            let random_transform_public_key =
-                block_obj.get(cx, "randomTransformPublicKey").unwrap();
-            let random_transform_encrypted_temp_key = block_obj
-                .get(cx, "randomTransformEncryptedTempKey")
-                .unwrap();
-
-            TransformBlock::new(
-                &js_object_to_public_key(cx, public_key),
-                &EncryptedTempKey::new(buffer_to_fixed_384_bytes(
+        /// Convert a Recrypt PublicKey struct into a JsObject with x/y properties which are Buffers
+        pub fn public_key_to_js_object<'a, T: Context<'a>>(
+            cx: &mut T,
+            public_key: &PublicKey,
+        ) -> NeonResult<Handle<'a, JsObject>> {
+            let public_key_obj: Handle<JsObject> = cx.empty_object();
+            let (x, y) = public_key.bytes_x_y();
+            let public_key_x_buffer = bytes_to_buffer(cx, x)?;
+            let public_key_y_buffer = bytes_to_buffer(cx, y)?;
+
+            public_key_obj.set(cx, "x", public_key_x_buffer)?;
+            public_key_obj.set(cx, "y", public_key_y_buffer)?;
+            Ok(public_key_obj)
+        }
+
+        /// Convert a JsObject which represents a TransformKey into an internal recrypt TransformKey
+        pub fn js_object_to_transform_key<'a, T: Context<'a>>(
+            cx: &mut T,
+            object: Handle<JsObject>,
+            field_path: &str,
+        ) -> NeonResult<TransformKey> {
+            let encrypted_temp_key_buffer =
+                get_field(cx, object, field_path, "encryptedTempKey", "Buffer")?;
+            let ephemeral_public_key_obj =
+                get_field(cx, object, field_path, "ephemeralPublicKey", "object")?;
+            let hashed_temp_key_buffer =
+                get_field(cx, object, field_path, "hashedTempKey", "Buffer")?;
+            let public_signing_key_buffer =
+                get_field(cx, object, field_path, "publicSigningKey", "Buffer")?;
+            let signature_buffer = get_field(cx, object, field_path, "signature", "Buffer")?;
+            let to_public_key_obj = get_field(cx, object, field_path, "toPublicKey", "object")?;
+
+            let ephemeral_public_key = js_object_to_public_key(
+                cx,
+                ephemeral_public_key_obj,
+                &format!("{}.ephemeralPublicKey", field_path),
+            )?;
+            let to_public_key = js_object_to_public_key(
+                cx,
+                to_public_key_obj,
+                &format!("{}.toPublicKey", field_path),
+            )?;
+            let encrypted_temp_key = EncryptedTempKey::new($fp12_bytes(
+                cx,
+                encrypted_temp_key_buffer,
+                &format!("{}.encryptedTempKey", field_path),
+            )?);
+            let hashed_temp_key_path = format!("{}.hashedTempKey", field_path);
+            let hashed_temp_key_bytes =
+                $hashed_value_bytes(cx, hashed_temp_key_buffer, &hashed_temp_key_path)?;
+            let hashed_temp_key = match HashedValue::new(hashed_temp_key_bytes) {
+                Ok(hashed_value) => hashed_value,
+                Err(e) => {
+                    return throw_error(
+                        cx,
+                        ErrorKind::Error,
+                        ErrorCode::InvalidHashedValue,
+                        Some(&hashed_temp_key_path),
+                        &format!(
+                            "Provided value for '{}' is invalid. {}",
+                            hashed_temp_key_path, e
+                        ),
+                    );
+                }
+            };
+            let public_signing_key = PublicSigningKey::new(buffer_to_fixed_32_bytes(
+                cx,
+                public_signing_key_buffer,
+                &format!("{}.publicSigningKey", field_path),
+            )?);
+            let signature = buffer_to_ed25519_signature(
+                cx,
+                signature_buffer,
+                &format!("{}.signature", field_path),
+            )?;
+
+            Ok(TransformKey::new(
+                ephemeral_public_key,
+                to_public_key,
+                encrypted_temp_key,
+                hashed_temp_key,
+                public_signing_key,
+                signature,
+            ))
+        }
+
+        /// Convert a Recrypt TransformKey into a JsObject with expected properties and bytes converted to Buffers
+        pub fn transform_key_to_js_object<'a, T: Context<'a>>(
+            cx: &mut T,
+            transform_key: TransformKey,
+        ) -> NeonResult<Handle<'a, JsObject>> {
+            let transform_key_obj = cx.empty_object();
+            let to_public_key = public_key_to_js_object(cx, transform_key.to_public_key())?;
+            let ephemeral_public_key =
+                public_key_to_js_object(cx, transform_key.ephemeral_public_key())?;
+            let encrypted_temp_key_buffer =
+                bytes_to_buffer(cx, transform_key.encrypted_temp_key().bytes())?;
+            let hashed_temp_key_buffer =
+                bytes_to_buffer(cx, transform_key.hashed_temp_key().bytes())?;
+            let public_signing_key_buffer =
+                bytes_to_buffer(cx, transform_key.public_signing_key().bytes())?;
+            let signature_buffer: Handle<JsBuffer> =
+                bytes_to_buffer(cx, transform_key.signature().bytes())?;
+
+            transform_key_obj.set(cx, "toPublicKey", to_public_key)?;
+            transform_key_obj.set(cx, "ephemeralPublicKey", ephemeral_public_key)?;
+            transform_key_obj.set(cx, "encryptedTempKey", encrypted_temp_key_buffer)?;
+            transform_key_obj.set(cx, "hashedTempKey", hashed_temp_key_buffer)?;
+            transform_key_obj.set(cx, "publicSigningKey", public_signing_key_buffer)?;
+            transform_key_obj.set(cx, "signature", signature_buffer)?;
+            Ok(transform_key_obj)
+        }
+
+        /// Convert an array of transform blocks into a non-empty vector of internal recrypt TransformBlock structs.
+        pub fn js_object_to_transform_blocks<'a, T: Context<'a>>(
+            cx: &mut T,
+            js_array: Handle<JsArray>,
+            field_path: &str,
+        ) -> NeonResult<NonEmptyVec<TransformBlock>> {
+            let transform_blocks: Vec<Handle<JsValue>> = js_array.to_vec(cx)?;
+            let mut blocks: Vec<TransformBlock> = Vec::with_capacity(transform_blocks.len());
+
+            for (i, block) in transform_blocks.into_iter().enumerate() {
+                let block_path = format!("{}[{}]", field_path, i);
+                let block_obj = downcast_field::<JsObject, _>(cx, block, &block_path, "object")?;
+                let public_key = get_field(cx, block_obj, &block_path, "publicKey", "object")?;
+                let encrypted_temp_key =
+                    get_field(cx, block_obj, &block_path, "encryptedTempKey", "Buffer")?;
+                let random_transform_public_key = get_field(
+                    cx,
+                    block_obj,
+                    &block_path,
+                    "randomTransformPublicKey",
+                    "object",
+                )?;
+                let random_transform_encrypted_temp_key = get_field(
+                    cx,
+                    block_obj,
+                    &block_path,
+                    "randomTransformEncryptedTempKey",
+                    "Buffer",
+                )?;
+
+                let public_key =
+                    js_object_to_public_key(cx, public_key, &format!("{}.publicKey", block_path))?;
+                let encrypted_temp_key = EncryptedTempKey::new($fp12_bytes(
                     cx,
                     encrypted_temp_key,
-                    "transformBlock.encryptedTempKey",
-                )),
-                &js_object_to_public_key(cx, random_transform_public_key),
-                &EncryptedTempKey::new(buffer_to_fixed_384_bytes(
+                    &format!("{}.encryptedTempKey", block_path),
+                )?);
+                let random_transform_public_key = js_object_to_public_key(
+                    cx,
+                    random_transform_public_key,
+                    &format!("{}.randomTransformPublicKey", block_path),
+                )?;
+                let random_transform_encrypted_temp_key = EncryptedTempKey::new($fp12_bytes(
                     cx,
                     random_transform_encrypted_temp_key,
-                    "transformBlock.randomTransformEncryptedTempKey",
-                )),
-            )
-            .unwrap()
-        })
-        .collect();
-
-    NonEmptyVec::try_from(&blocks).unwrap()
-}
+                    &format!("{}.randomTransformEncryptedTempKey", block_path),
+                )?);
 
-/// Iterate through the provided internal TransformBlocks and convert each block to an external array of transform block objects.
-pub fn transform_blocks_to_js_object<'a, T: Context<'a>>(
-    cx: &mut T,
-    transform_blocks: Vec<TransformBlock>,
-) -> NeonResult<Handle<'a, JsArray>> {
-    let blocks_array: Handle<JsArray> = JsArray::new(cx, transform_blocks.len() as u32);
-
-    for (i, block_rs) in transform_blocks.iter().enumerate() {
-        let block = cx.empty_object();
-
-        let public_key = public_key_to_js_object(cx, block_rs.public_key())?;
-        let encrypted_temp_key = bytes_to_buffer(cx, block_rs.encrypted_temp_key().bytes())?;
-        let random_transform_public_key =
-            public_key_to_js_object(cx, block_rs.random_transform_public_key())?;
-        let encrypted_random_transform_temp_key =
-            bytes_to_buffer(cx, block_rs.encrypted_random_transform_temp_key().bytes())?;
-
-        block.set(cx, "publicKey", public_key)?;
-        block.set(cx, "encryptedTempKey", encrypted_temp_key)?;
-        block.set(cx, "randomTransformPublicKey", random_transform_public_key)?;
-        block.set(
-            cx,
-            "randomTransformEncryptedTempKey",
-            encrypted_random_transform_temp_key,
-        )?;
+                match TransformBlock::new(
+                    &public_key,
+                    &encrypted_temp_key,
+                    &random_transform_public_key,
+                    &random_transform_encrypted_temp_key,
+                ) {
+                    Ok(transform_block) => blocks.push(transform_block),
+                    Err(e) => {
+                        return throw_error(
+                            cx,
+                            ErrorKind::Error,
+                            ErrorCode::InvalidPublicKey,
+                            Some(&block_path),
+                            &format!("Provided value for '{}' is invalid. {}", block_path, e),
+                        );
+                    }
+                }
+            }
 
-        blocks_array.set(cx, i as u32, block)?;
-    }
-    Ok(blocks_array)
-}
+            match NonEmptyVec::try_from(&blocks) {
+                Ok(blocks) => Ok(blocks),
+                Err(_) => throw_error(
+                    cx,
+                    ErrorKind::RangeError,
+                    ErrorCode::EmptyArray,
+                    Some(field_path),
+                    &format!("Provided array for '{}' must not be empty.", field_path),
+                ),
+            }
+        }
 
-/// Convert a JsObject with various encrypted value keys into a EncryptedOnce or TransformedValue value.
-pub fn js_object_to_encrypted_value<'a, T: Context<'a>>(
-    cx: &mut T,
-    object: Handle<JsObject>,
-) -> EncryptedValue {
-    let emphemeral_public_key_obj = object
-        .get::<JsObject, _, _>(cx, "ephemeralPublicKey")
-        .unwrap();
-    let encrypted_message_buffer = object
-        .get::<JsBuffer, _, _>(cx, "encryptedMessage")
-        .unwrap();
-    let auth_hash_buffer = object.get::<JsBuffer, _, _>(cx, "authHash").unwrap();
-    let public_signing_key_buffer = object
-        .get::<JsBuffer, _, _>(cx, "publicSigningKey")
-        .unwrap();
-    let signature_buffer = object.get::<JsBuffer, _, _>(cx, "signature").unwrap();
-    let transform_blocks = object.get::<JsArray, _, _>(cx, "transformBlocks").unwrap();
-
-    // create the encrypted value and return it
-    if transform_blocks.len(cx) > 0 {
-        EncryptedValue::TransformedValue {
-            ephemeral_public_key: js_object_to_public_key(cx, emphemeral_public_key_obj),
-            encrypted_message: EncryptedMessage::new(buffer_to_fixed_384_bytes(
-                cx,
-                encrypted_message_buffer,
-                "encryptedMessage",
-            )),
-            auth_hash: AuthHash::new(buffer_to_fixed_32_bytes(cx, auth_hash_buffer, "authHash")),
-            public_signing_key: PublicSigningKey::new(buffer_to_fixed_32_bytes(
-                cx,
-                public_signing_key_buffer,
-                "publicSigningKey",
-            )),
-            signature: buffer_to_ed25519_signature(cx, signature_buffer),
-            transform_blocks: js_object_to_transform_blocks(cx, transform_blocks),
+        /// Iterate through the provided internal TransformBlocks and convert each block to an external array of transform block objects.
+        pub fn transform_blocks_to_js_object<'a, T: Context<'a>>(
+            cx: &mut T,
+            transform_blocks: Vec<TransformBlock>,
+        ) -> NeonResult<Handle<'a, JsArray>> {
+            let blocks_array: Handle<JsArray> = JsArray::new(cx, transform_blocks.len() as u32);
+
+            for (i, block_rs) in transform_blocks.iter().enumerate() {
+                let block = cx.empty_object();
+
+                let public_key = public_key_to_js_object(cx, block_rs.public_key())?;
+                let encrypted_temp_key =
+                    bytes_to_buffer(cx, block_rs.encrypted_temp_key().bytes())?;
+                let random_transform_public_key =
+                    public_key_to_js_object(cx, block_rs.random_transform_public_key())?;
+                let encrypted_random_transform_temp_key =
+                    bytes_to_buffer(cx, block_rs.encrypted_random_transform_temp_key().bytes())?;
+
+                block.set(cx, "publicKey", public_key)?;
+                block.set(cx, "encryptedTempKey", encrypted_temp_key)?;
+                block.set(cx, "randomTransformPublicKey", random_transform_public_key)?;
+                block.set(
+                    cx,
+                    "randomTransformEncryptedTempKey",
+                    encrypted_random_transform_temp_key,
+                )?;
+
+                blocks_array.set(cx, i as u32, block)?;
+            }
+            Ok(blocks_array)
         }
-    } else {
-        EncryptedValue::EncryptedOnceValue {
-            ephemeral_public_key: js_object_to_public_key(cx, emphemeral_public_key_obj),
-            encrypted_message: EncryptedMessage::new(buffer_to_fixed_384_bytes(
+
+        /// Convert a JsObject with various encrypted value keys into a EncryptedOnce or TransformedValue value.
+        pub fn js_object_to_encrypted_value<'a, T: Context<'a>>(
+            cx: &mut T,
+            object: Handle<JsObject>,
+            field_path: &str,
+        ) -> NeonResult<EncryptedValue> {
+            let emphemeral_public_key_obj =
+                get_field(cx, object, field_path, "ephemeralPublicKey", "object")?;
+            let encrypted_message_buffer =
+                get_field(cx, object, field_path, "encryptedMessage", "Buffer")?;
+            let auth_hash_buffer = get_field(cx, object, field_path, "authHash", "Buffer")?;
+            let public_signing_key_buffer =
+                get_field(cx, object, field_path, "publicSigningKey", "Buffer")?;
+            let signature_buffer = get_field(cx, object, field_path, "signature", "Buffer")?;
+            let transform_blocks =
+                get_field::<JsArray, _>(cx, object, field_path, "transformBlocks", "Array")?;
+
+            let ephemeral_public_key = js_object_to_public_key(
+                cx,
+                emphemeral_public_key_obj,
+                &format!("{}.ephemeralPublicKey", field_path),
+            )?;
+            let encrypted_message = EncryptedMessage::new($fp12_bytes(
                 cx,
                 encrypted_message_buffer,
-                "encryptedMessage",
-            )),
-            auth_hash: AuthHash::new(buffer_to_fixed_32_bytes(cx, auth_hash_buffer, "authHash")),
-            public_signing_key: PublicSigningKey::new(buffer_to_fixed_32_bytes(
+                &format!("{}.encryptedMessage", field_path),
+            )?);
+            let auth_hash = AuthHash::new(buffer_to_fixed_32_bytes(
+                cx,
+                auth_hash_buffer,
+                &format!("{}.authHash", field_path),
+            )?);
+            let public_signing_key = PublicSigningKey::new(buffer_to_fixed_32_bytes(
                 cx,
                 public_signing_key_buffer,
-                "publicSigningKey",
-            )),
-            signature: buffer_to_ed25519_signature(cx, signature_buffer),
+                &format!("{}.publicSigningKey", field_path),
+            )?);
+            let signature = buffer_to_ed25519_signature(
+                cx,
+                signature_buffer,
+                &format!("{}.signature", field_path),
+            )?;
+
+            // create the encrypted value and return it
+            if transform_blocks.len(cx) > 0 {
+                Ok(EncryptedValue::TransformedValue {
+                    ephemeral_public_key,
+                    encrypted_message,
+                    auth_hash,
+                    public_signing_key,
+                    signature,
+                    transform_blocks: js_object_to_transform_blocks(
+                        cx,
+                        transform_blocks,
+                        &format!("{}.transformBlocks", field_path),
+                    )?,
+                })
+            } else {
+                Ok(EncryptedValue::EncryptedOnceValue {
+                    ephemeral_public_key,
+                    encrypted_message,
+                    auth_hash,
+                    public_signing_key,
+                    signature,
+                })
+            }
         }
-    }
+
+        /// Convert a Recrypt EncryptedValue into a JsObject with expected properties and bytes converted to Buffers.
+        pub fn encrypted_value_to_js_object<'a, T: Context<'a>>(
+            cx: &mut T,
+            encrypted_value: EncryptedValue,
+        ) -> NeonResult<Handle<'a, JsObject>> {
+            let encrypted_value_obj = cx.empty_object();
+
+            let encrypted_value_tuple = match encrypted_value {
+                EncryptedValue::EncryptedOnceValue {
+                    ephemeral_public_key,
+                    encrypted_message,
+                    auth_hash,
+                    public_signing_key,
+                    signature,
+                } => (
+                    ephemeral_public_key,
+                    encrypted_message,
+                    auth_hash,
+                    public_signing_key,
+                    signature,
+                    vec![],
+                ),
+                EncryptedValue::TransformedValue {
+                    ephemeral_public_key,
+                    encrypted_message,
+                    auth_hash,
+                    public_signing_key,
+                    signature,
+                    transform_blocks,
+                } => (
+                    ephemeral_public_key,
+                    encrypted_message,
+                    auth_hash,
+                    public_signing_key,
+                    signature,
+                    transform_blocks.to_vec(),
+                ),
+            };
+
+            let ephemeral_public_key = public_key_to_js_object(cx, &encrypted_value_tuple.0)?;
+            let encrypted_message_buffer = bytes_to_buffer(cx, (encrypted_value_tuple.1).bytes())?;
+            let auth_hash_buffer = bytes_to_buffer(cx, encrypted_value_tuple.2.bytes())?;
+            let public_signing_key_buffer = bytes_to_buffer(cx, encrypted_value_tuple.3.bytes())?;
+            let signature_buffer = bytes_to_buffer(cx, encrypted_value_tuple.4.bytes())?;
+            let transform_blocks = transform_blocks_to_js_object(cx, encrypted_value_tuple.5)?;
+
+            encrypted_value_obj.set(cx, "ephemeralPublicKey", ephemeral_public_key)?;
+            encrypted_value_obj.set(cx, "encryptedMessage", encrypted_message_buffer)?;
+            encrypted_value_obj.set(cx, "authHash", auth_hash_buffer)?;
+            encrypted_value_obj.set(cx, "publicSigningKey", public_signing_key_buffer)?;
+            encrypted_value_obj.set(cx, "signature", signature_buffer)?;
+            encrypted_value_obj.set(cx, "transformBlocks", transform_blocks)?;
+            Ok(encrypted_value_obj)
+        }
+    };
 }
 
-/// Convert a Recrypt EncryptedValue into a JsObject with expected properties and bytes converted to Buffers.
-pub fn encrypted_value_to_js_object<'a, T: Context<'a>>(
-    cx: &mut T,
-    encrypted_value: EncryptedValue,
-) -> NeonResult<Handle<'a, JsObject>> {
-    let encrypted_value_obj = cx.empty_object();
-
-    let encrypted_value_tuple = match encrypted_value {
-        EncryptedValue::EncryptedOnceValue {
-            ephemeral_public_key,
-            encrypted_message,
-            auth_hash,
-            public_signing_key,
-            signature,
-        } => (
-            ephemeral_public_key,
-            encrypted_message,
-            auth_hash,
-            public_signing_key,
-            signature,
-            vec![],
-        ),
-        EncryptedValue::TransformedValue {
-            ephemeral_public_key,
-            encrypted_message,
-            auth_hash,
-            public_signing_key,
-            signature,
-            transform_blocks,
-        } => (
-            ephemeral_public_key,
-            encrypted_message,
-            auth_hash,
-            public_signing_key,
-            signature,
-            transform_blocks.to_vec(),
-        ),
+// The 256-bit converters live at the top level of this module.
+curve_converters! {
+    private_key: buffer_to_fixed_32_bytes,
+    coordinate: buffer_to_fixed_32_bytes,
+    fp12: buffer_to_fixed_384_bytes,
+    hashed_value: buffer_to_fixed_128_bytes,
+    schnorr_signature: buffer_to_fixed_64_bytes,
+    compressed_public_key: buffer_to_fixed_33_bytes,
+    decompress_public_key: compression::decompress_public_key,
+}
+
+/// Converters for the types of the 480-bit API.
+pub mod api480 {
+    use super::{
+        ErrorCode, ErrorKind, buffer_to_ed25519_signature, buffer_to_fixed_32_bytes,
+        buffer_to_fixed_60_bytes, buffer_to_fixed_61_bytes, buffer_to_fixed_120_bytes,
+        buffer_to_fixed_240_bytes, buffer_to_fixed_720_bytes, bytes_to_buffer, downcast_field,
+        get_field, throw_error,
     };
+    use crate::compression;
+    use neon::{prelude::*, types::JsBuffer};
+    use recrypt::api_480::{
+        AuthHash, EncryptedMessage, EncryptedTempKey, EncryptedValue, HashedValue, Plaintext,
+        PrivateKey, PublicKey, PublicSigningKey, SchnorrSignature, TransformBlock, TransformKey,
+    };
+    use recrypt::nonemptyvec::NonEmptyVec;
 
-    let ephemeral_public_key = public_key_to_js_object(cx, &encrypted_value_tuple.0)?;
-    let encrypted_message_buffer = bytes_to_buffer(cx, (encrypted_value_tuple.1).bytes())?;
-    let auth_hash_buffer = bytes_to_buffer(cx, encrypted_value_tuple.2.bytes())?;
-    let public_signing_key_buffer = bytes_to_buffer(cx, encrypted_value_tuple.3.bytes())?;
-    let signature_buffer = bytes_to_buffer(cx, encrypted_value_tuple.4.bytes())?;
-    let transform_blocks = transform_blocks_to_js_object(cx, encrypted_value_tuple.5)?;
-
-    encrypted_value_obj.set(cx, "ephemeralPublicKey", ephemeral_public_key)?;
-    encrypted_value_obj.set(cx, "encryptedMessage", encrypted_message_buffer)?;
-    encrypted_value_obj.set(cx, "authHash", auth_hash_buffer)?;
-    encrypted_value_obj.set(cx, "publicSigningKey", public_signing_key_buffer)?;
-    encrypted_value_obj.set(cx, "signature", signature_buffer)?;
-    encrypted_value_obj.set(cx, "transformBlocks", transform_blocks)?;
-    Ok(encrypted_value_obj)
+    curve_converters! {
+        private_key: buffer_to_fixed_60_bytes,
+        coordinate: buffer_to_fixed_60_bytes,
+        fp12: buffer_to_fixed_720_bytes,
+        hashed_value: buffer_to_fixed_240_bytes,
+        schnorr_signature: buffer_to_fixed_120_bytes,
+        compressed_public_key: buffer_to_fixed_61_bytes,
+        decompress_public_key: compression::decompress_public_key_480,
+    }
 }
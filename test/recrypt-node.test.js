@@ -2,6 +2,7 @@
 const matchers = require("jest-extended");
 expect.extend(matchers);
 
+const crypto = require("crypto");
 const recrypt = require("../index.js");
 //Randomly generated legit ED25519 keypair
 const publicSigningKey = Buffer.from("LQPVx4NPqxSFQO/p6JgA4xGydUfxnfsRwTu/VJy6sQ0=", "base64");
@@ -11,6 +12,44 @@ describe("Recrypt-Node", () => {
     describe("Api256", () => {
         const api = new recrypt.Api256();
 
+        describe("seeded instances", () => {
+            const seed = Buffer.alloc(32, 7);
+
+            test("should not be deterministic by default", () => {
+                expect(api.isDeterministic()).toBeFalse();
+            });
+
+            test("should generate the same values from the same seed", () => {
+                const api1 = new recrypt.Api256({seed});
+                const api2 = new recrypt.Api256({seed});
+                expect(api1.isDeterministic()).toBeTrue();
+
+                const keys = api1.generateKeyPair();
+                expect(api2.generateKeyPair()).toEqual(keys);
+                expect(api2.generateEd25519KeyPair()).toEqual(api1.generateEd25519KeyPair());
+
+                const plaintext = api1.generatePlaintext();
+                expect(api2.generatePlaintext()).toEqual(plaintext);
+                expect(api2.encrypt(plaintext, keys.publicKey, privateSigningKey)).toEqual(
+                    api1.encrypt(plaintext, keys.publicKey, privateSigningKey)
+                );
+            });
+
+            test("should generate different values from different seeds", () => {
+                const api1 = new recrypt.Api256({seed});
+                const api2 = new recrypt.Api256({seed: Buffer.alloc(32, 8)});
+
+                expect(api2.generateKeyPair()).not.toEqual(api1.generateKeyPair());
+            });
+
+            test("should reject seeds of the wrong length", () => {
+                expect(() => new recrypt.Api256({seed: Buffer.alloc(31)})).toThrow(
+                    expect.objectContaining({code: "InvalidBufferLength", field: "options.seed"})
+                );
+                expect(() => new recrypt.Api256({seed: "seed"})).toThrow(TypeError);
+            });
+        });
+
         describe("generateKeyPair", () => {
             test("should generate keypairs of the expected length", () => {
                 const keypair = api.generateKeyPair();
@@ -27,6 +66,82 @@ describe("Recrypt-Node", () => {
             });
         });
 
+        describe("private key handles", () => {
+            test("should generate a handle which never exposes the key bytes", () => {
+                const keypair = api.generateKeyPair({handle: true});
+                expect(keypair.privateKey).not.toBeInstanceOf(Buffer);
+                expect(Object.keys(keypair.privateKey)).toEqual([]);
+                expect(JSON.stringify(keypair.privateKey)).toEqual("{}");
+
+                const privateKey = recrypt.privateKeyHandleToBytes(keypair.privateKey);
+                expect(privateKey).toHaveLength(32);
+                expect(api.computePublicKey(privateKey)).toEqual(keypair.publicKey);
+                expect(api.computePublicKey(keypair.privateKey)).toEqual(keypair.publicKey);
+                expect(api.computePublicKey(recrypt.privateKeyHandleFromBytes(privateKey))).toEqual(keypair.publicKey);
+            });
+
+            test("should be accepted everywhere a private key is", () => {
+                const from = api.generateKeyPair({handle: true});
+                const to = api.generateKeyPair({handle: true});
+                const plaintext = api.generatePlaintext();
+
+                const encrypted = api.encrypt(plaintext, from.publicKey, privateSigningKey);
+                expect(api.decrypt(encrypted, from.privateKey)).toEqual(plaintext);
+                const transformKey = api.generateTransformKey(from.privateKey, to.publicKey, privateSigningKey);
+                expect(api.decrypt(api.transform(encrypted, transformKey, privateSigningKey), to.privateKey)).toEqual(plaintext);
+
+                const message = Buffer.from("message");
+                const signature = api.schnorrSign(from.privateKey, from.publicKey, message);
+                expect(api.schnorrVerify(from.publicKey, undefined, message, signature)).toBeTrue();
+
+                const sum = recrypt.addPrivateKeys(from.privateKey, recrypt.privateKeyHandleToBytes(to.privateKey));
+                expect(sum).not.toBeInstanceOf(Buffer);
+                expect(recrypt.subtractPrivateKeys(sum, to.privateKey)).not.toBeInstanceOf(Buffer);
+                expect(recrypt.privateKeyHandleToBytes(recrypt.subtractPrivateKeys(sum, to.privateKey))).toEqual(
+                    recrypt.privateKeyHandleToBytes(from.privateKey)
+                );
+            });
+
+            test("should reject values which are neither Buffers nor handles", () => {
+                expect(() => api.computePublicKey("key")).toThrow(expect.objectContaining({code: "InvalidFieldType", field: "privateKey"}));
+                expect(() => api.computePublicKey(api.generateKeyPair().publicKey)).toThrow(TypeError);
+                expect(() => recrypt.privateKeyHandleToBytes(Buffer.alloc(32))).toThrow(TypeError);
+            });
+        });
+
+        describe("signing key pair handles", () => {
+            test("should generate and import handles which work anywhere a private signing key does", () => {
+                const signingKeys = api.generateEd25519KeyPair({handle: true});
+                expect(signingKeys.privateKey).not.toBeInstanceOf(Buffer);
+                expect(signingKeys.publicKey).toHaveLength(32);
+                expect(api.computeEd25519PublicKey(signingKeys.privateKey)).toEqual(signingKeys.publicKey);
+
+                const bytes = recrypt.signingKeyPairHandleToBytes(signingKeys.privateKey);
+                expect(bytes).toHaveLength(64);
+                const imported = recrypt.signingKeyPairHandleFromBytes(bytes);
+                const message = Buffer.from("message");
+                expect(api.ed25519Sign(imported, message)).toEqual(api.ed25519Sign(bytes, message));
+
+                const keys = api.generateKeyPair();
+                const plaintext = api.generatePlaintext();
+                const encrypted = api.encrypt(plaintext, keys.publicKey, signingKeys.privateKey);
+                expect(encrypted.publicSigningKey).toEqual(signingKeys.publicKey);
+                const toKeys = api.generateKeyPair();
+                const transformKey = api.generateTransformKey(keys.privateKey, toKeys.publicKey, imported);
+                const transformed = api.transform(encrypted, transformKey, signingKeys.privateKey);
+                expect(api.decrypt(transformed, toKeys.privateKey)).toEqual(plaintext);
+            });
+
+            test("should reject invalid key bytes and values which are neither Buffers nor handles", () => {
+                const bytes = recrypt.signingKeyPairHandleToBytes(api.generateEd25519KeyPair({handle: true}).privateKey);
+                bytes[40] ^= 1;
+                expect(() => recrypt.signingKeyPairHandleFromBytes(bytes)).toThrow(expect.objectContaining({code: "InvalidSigningKeyPair"}));
+                expect(() => api.ed25519Sign(api.generateKeyPair({handle: true}).privateKey, Buffer.from("message"))).toThrow(
+                    expect.objectContaining({code: "InvalidFieldType", field: "privateSigningKey"})
+                );
+            });
+        });
+
         describe("generateEd25519KeyPair", () => {
             test("should generate ed25519 keypairs of the expected length", () => {
                 const keypair = api.generateEd25519KeyPair();
@@ -167,6 +282,46 @@ describe("Recrypt-Node", () => {
             });
         });
 
+        describe("encryptMany", () => {
+            test("should encrypt the plaintext to every public key in order", () => {
+                const plaintext = api.generatePlaintext();
+                const keys = [api.generateKeyPair(), api.generateKeyPair(), api.generateKeyPair()];
+
+                const encryptedValues = api.encryptMany(
+                    plaintext,
+                    keys.map((k) => k.publicKey),
+                    privateSigningKey
+                );
+
+                expect(encryptedValues).toBeArrayOfSize(3);
+                encryptedValues.forEach((encryptedValue, i) => {
+                    expect(Object.keys(encryptedValue)).toHaveLength(6);
+                    expect(encryptedValue.publicSigningKey).toEqual(publicSigningKey);
+                    expect(api.decrypt(encryptedValue, keys[i].privateKey)).toEqual(plaintext);
+                });
+            });
+
+            test("should return per recipient errors without failing the batch", () => {
+                const plaintext = api.generatePlaintext();
+                const goodKeys = api.generateKeyPair();
+                const badPublicKey = {x: goodKeys.publicKey.x, y: Buffer.alloc(12)};
+
+                const results = api.encryptMany(plaintext, [badPublicKey, goodKeys.publicKey, "nope"], privateSigningKey);
+
+                expect(results).toBeArrayOfSize(3);
+                expect(results[0]).toBeInstanceOf(RangeError);
+                expect(results[0].code).toBe("InvalidBufferLength");
+                expect(results[0].field).toBe("toPublicKeys[0].y");
+                expect(api.decrypt(results[1], goodKeys.privateKey)).toEqual(plaintext);
+                expect(results[2]).toBeInstanceOf(TypeError);
+                expect(results[2].field).toBe("toPublicKeys[2]");
+            });
+
+            test("should throw if the signing key is invalid", () => {
+                expect(() => api.encryptMany(api.generatePlaintext(), [api.generateKeyPair().publicKey], Buffer.alloc(3))).toThrow(RangeError);
+            });
+        });
+
         describe("transform", () => {
             it("generates expected value for level 1 transform", () => {
                 const keys = api.generateKeyPair();
@@ -300,6 +455,47 @@ describe("Recrypt-Node", () => {
             });
         });
 
+        describe("transformMany", () => {
+            const userKeys = api.generateKeyPair();
+            const deviceKeys = api.generateKeyPair();
+            const transformKey = api.generateTransformKey(userKeys.privateKey, deviceKeys.publicKey, privateSigningKey);
+
+            test.each([[false], [true]])("should transform every value in order (parallel: %s)", (parallel) => {
+                const plaintexts = [api.generatePlaintext(), api.generatePlaintext(), api.generatePlaintext()];
+                const encryptedValues = plaintexts.map((pt) => api.encrypt(pt, userKeys.publicKey, privateSigningKey));
+
+                const transformed = api.transformMany(encryptedValues, transformKey, privateSigningKey, {parallel});
+
+                expect(transformed).toBeArrayOfSize(3);
+                transformed.forEach((encryptedValue, i) => {
+                    expect(encryptedValue.transformBlocks).toBeArrayOfSize(1);
+                    expect(api.decrypt(encryptedValue, deviceKeys.privateKey)).toEqual(plaintexts[i]);
+                });
+            });
+
+            test("should return per value errors without failing the batch", () => {
+                const plaintext = api.generatePlaintext();
+                const good = api.encrypt(plaintext, userKeys.publicKey, privateSigningKey);
+                const tampered = api.encrypt(plaintext, userKeys.publicKey, privateSigningKey);
+                tampered.signature[0] ^= 1;
+                const missingField = api.encrypt(plaintext, userKeys.publicKey, privateSigningKey);
+                delete missingField.encryptedMessage;
+
+                const results = api.transformMany([tampered, good, missingField], transformKey, privateSigningKey, {parallel: true});
+
+                expect(results).toBeArrayOfSize(3);
+                expect(results[0]).toBeInstanceOf(Error);
+                expect(results[0].code).toBe("InvalidEncryptedMessageSignature");
+                expect(api.decrypt(results[1], deviceKeys.privateKey)).toEqual(plaintext);
+                expect(results[2]).toBeInstanceOf(TypeError);
+                expect(results[2].field).toBe("encryptedValues[2].encryptedMessage");
+            });
+
+            test("should return an empty array for an empty batch", () => {
+                expect(api.transformMany([], transformKey, privateSigningKey)).toBeArrayOfSize(0);
+            });
+        });
+
         describe("decrypt roundtrip", () => {
             test("should be able to roundtrip decrypt a level 0 encrypted value", () => {
                 const plaintext = api.generatePlaintext();
@@ -363,6 +559,789 @@ describe("Recrypt-Node", () => {
             });
         });
 
+        describe("async variants", () => {
+            test("generateTransformKeyAsync should resolve with a transform key", async () => {
+                const fromPrivateKey = api.generateKeyPair().privateKey;
+                const toPublicKey = api.generateKeyPair().publicKey;
+
+                const transformKey = await api.generateTransformKeyAsync(fromPrivateKey, toPublicKey, privateSigningKey);
+                expect(Object.keys(transformKey)).toHaveLength(6);
+                expect(transformKey.toPublicKey).toEqual(toPublicKey);
+                expect(transformKey.publicSigningKey).toEqual(publicSigningKey);
+            });
+
+            test("should roundtrip encrypt, transform and decrypt", async () => {
+                const plaintext = api.generatePlaintext();
+                const userKeys = api.generateKeyPair();
+                const deviceKeys = api.generateKeyPair();
+                const transformKey = await api.generateTransformKeyAsync(userKeys.privateKey, deviceKeys.publicKey, privateSigningKey);
+
+                const lvl0EncryptedValue = await api.encryptAsync(plaintext, userKeys.publicKey, privateSigningKey);
+                expect(Object.keys(lvl0EncryptedValue)).toHaveLength(6);
+                expect(lvl0EncryptedValue.transformBlocks).toBeArrayOfSize(0);
+                expect(await api.decryptAsync(lvl0EncryptedValue, userKeys.privateKey)).toEqual(plaintext);
+
+                const lvl1EncryptedValue = await api.transformAsync(lvl0EncryptedValue, transformKey, privateSigningKey);
+                expect(lvl1EncryptedValue.transformBlocks).toBeArrayOfSize(1);
+                expect(await api.decryptAsync(lvl1EncryptedValue, deviceKeys.privateKey)).toEqual(plaintext);
+                expect(api.decrypt(lvl1EncryptedValue, deviceKeys.privateKey)).toEqual(plaintext);
+            });
+
+            test("should reject when the recrypt operation fails", async () => {
+                const keys = api.generateKeyPair();
+                const encryptedValue = await api.encryptAsync(api.generatePlaintext(), keys.publicKey, privateSigningKey);
+
+                await expect(api.decryptAsync(encryptedValue, api.generateKeyPair().privateKey)).rejects.toThrow(expect.objectContaining({code: "DecryptFailed"}));
+            });
+
+            test("should reject instead of throwing when inputs are invalid", async () => {
+                const keys = api.generateKeyPair();
+                const encryptedValue = api.encrypt(api.generatePlaintext(), keys.publicKey, privateSigningKey);
+                const encryptPromise = api.encryptAsync(Buffer.alloc(10), keys.publicKey, privateSigningKey);
+                expect(encryptPromise).toBeInstanceOf(Promise);
+                await expect(encryptPromise).rejects.toThrow(expect.objectContaining({code: "InvalidBufferLength", field: "plaintext"}));
+                await expect(api.transformAsync(encryptedValue, {}, privateSigningKey)).rejects.toThrow(
+                    expect.objectContaining({code: "MissingField", field: "transformKey.encryptedTempKey"})
+                );
+                await expect(api.decryptAsync({}, keys.privateKey)).rejects.toThrow(expect.objectContaining({code: "MissingField"}));
+                await expect(api.generateTransformKeyAsync(keys.privateKey, {x: Buffer.alloc(32)}, privateSigningKey)).rejects.toThrow(
+                    expect.objectContaining({code: "MissingField", field: "toPublicKey.y"})
+                );
+                await expect(api.decryptManyAsync([encryptedValue], Buffer.alloc(3))).rejects.toThrow(
+                    expect.objectContaining({code: "InvalidBufferLength", field: "privateKey"})
+                );
+                await expect(api.rotateGroupKeyAsync(keys.privateKey, [keys.publicKey], Buffer.alloc(3))).rejects.toThrow(
+                    expect.objectContaining({code: "InvalidBufferLength", field: "privateSigningKey"})
+                );
+            });
+        });
+
+        describe("decryptMany", () => {
+            const keys = api.generateKeyPair();
+
+            test.each([[false], [true]])("should decrypt every value in order (parallel: %s)", (parallel) => {
+                const plaintexts = [api.generatePlaintext(), api.generatePlaintext(), api.generatePlaintext()];
+                const encryptedValues = plaintexts.map((pt) => api.encrypt(pt, keys.publicKey, privateSigningKey));
+
+                expect(api.decryptMany(encryptedValues, keys.privateKey, {parallel})).toEqual(plaintexts);
+            });
+
+            test("should return derived symmetric keys when requested", () => {
+                const plaintexts = [api.generatePlaintext(), api.generatePlaintext()];
+                const encryptedValues = plaintexts.map((pt) => api.encrypt(pt, keys.publicKey, privateSigningKey));
+
+                expect(api.decryptMany(encryptedValues, keys.privateKey, {deriveSymmetricKey: true})).toEqual(
+                    plaintexts.map((pt) => api.deriveSymmetricKey(pt))
+                );
+            });
+
+            test("should return per value errors without failing the batch", () => {
+                const plaintext = api.generatePlaintext();
+                const good = api.encrypt(plaintext, keys.publicKey, privateSigningKey);
+                const otherKey = api.encrypt(plaintext, api.generateKeyPair().publicKey, privateSigningKey);
+
+                const results = api.decryptMany([otherKey, good, 42], keys.privateKey);
+
+                expect(results).toBeArrayOfSize(3);
+                expect(results[0].code).toBe("DecryptFailed");
+                expect(results[1]).toEqual(plaintext);
+                expect(results[2]).toBeInstanceOf(TypeError);
+                expect(results[2].field).toBe("encryptedValues[2]");
+            });
+
+            test("decryptManyAsync should resolve with results in order with per value errors", async () => {
+                const plaintexts = [api.generatePlaintext(), api.generatePlaintext()];
+                const encryptedValues = plaintexts.map((pt) => api.encrypt(pt, keys.publicKey, privateSigningKey));
+                const missingField = api.encrypt(api.generatePlaintext(), keys.publicKey, privateSigningKey);
+                delete missingField.authHash;
+
+                const results = await api.decryptManyAsync([encryptedValues[0], missingField, encryptedValues[1]], keys.privateKey, {parallel: true});
+
+                expect(results).toBeArrayOfSize(3);
+                expect(results[0]).toEqual(plaintexts[0]);
+                expect(results[1]).toBeInstanceOf(TypeError);
+                expect(results[1].field).toBe("encryptedValues[1].authHash");
+                expect(results[2]).toEqual(plaintexts[1]);
+            });
+        });
+
+        describe("encryptData/decryptData", () => {
+            const keys = api.generateKeyPair();
+            const data = Buffer.from("some data which is a lot longer than a single AES block");
+
+            test("should roundtrip data", () => {
+                const envelope = api.encryptData(data, keys.publicKey, privateSigningKey);
+                expect(Object.keys(envelope)).toEqual(["encryptedDek", "ciphertext"]);
+                expect(envelope.ciphertext).toHaveLength(12 + data.length + 16);
+                expect(envelope.encryptedDek.transformBlocks).toEqual([]);
+
+                expect(api.decryptData(envelope, keys.privateKey)).toEqual(data);
+            });
+
+            test("should roundtrip data with AAD and after a transform", () => {
+                const aad = Buffer.from("document-id");
+                const toKeys = api.generateKeyPair();
+                const envelope = api.encryptData(data, keys.publicKey, privateSigningKey, aad);
+                const transformKey = api.generateTransformKey(keys.privateKey, toKeys.publicKey, privateSigningKey);
+                const transformed = {...envelope, encryptedDek: api.transform(envelope.encryptedDek, transformKey, privateSigningKey)};
+
+                expect(api.decryptData(transformed, toKeys.privateKey, aad)).toEqual(data);
+            });
+
+            test("should fail to decrypt with the wrong AAD or a modified ciphertext", () => {
+                const envelope = api.encryptData(data, keys.publicKey, privateSigningKey, Buffer.from("aad"));
+                expect(() => api.decryptData(envelope, keys.privateKey)).toThrow(
+                    expect.objectContaining({code: "InvalidCiphertext", field: "envelope.ciphertext"})
+                );
+
+                const ciphertext = Buffer.from(envelope.ciphertext);
+                ciphertext[20] ^= 1;
+                expect(() => api.decryptData({...envelope, ciphertext}, keys.privateKey, Buffer.from("aad"))).toThrow(
+                    expect.objectContaining({code: "InvalidCiphertext"})
+                );
+                expect(() => api.decryptData({...envelope, ciphertext: Buffer.alloc(27)}, keys.privateKey)).toThrow(RangeError);
+            });
+
+            test("should fail to decrypt with the wrong private key", () => {
+                const envelope = api.encryptData(data, keys.publicKey, privateSigningKey);
+                expect(() => api.decryptData(envelope, api.generateKeyPair().privateKey)).toThrow(expect.objectContaining({code: "DecryptFailed"}));
+            });
+        });
+
+        describe("shared documents", () => {
+            const alice = api.generateKeyPair();
+            const bob = api.generateKeyPair();
+            const carol = api.generateKeyPair();
+            const data = Buffer.from("data shared with many users");
+
+            test("should encrypt once for every recipient", () => {
+                const document = api.createSharedDocument(data, {alice: alice.publicKey, bob: recrypt.compressPublicKey(bob.publicKey)}, privateSigningKey);
+                expect(Object.keys(document.encryptedDeks)).toEqual(["alice", "bob"]);
+                expect(document.ciphertext).toHaveLength(12 + data.length + 16);
+
+                expect(api.decryptSharedDocument(document, "alice", alice.privateKey)).toEqual(data);
+                expect(api.decryptSharedDocument(document, "bob", bob.privateKey)).toEqual(data);
+                expect(() => api.decryptSharedDocument(document, "bob", alice.privateKey)).toThrow(expect.objectContaining({code: "DecryptFailed"}));
+            });
+
+            test("should grant and revoke access without re-encrypting the data", () => {
+                const aad = Buffer.from("aad");
+                const document = api.createSharedDocument(data, {alice: alice.publicKey, bob: bob.publicKey}, privateSigningKey, aad);
+
+                const granted = api.grantAccess(document, "carol", carol.publicKey, bob.privateKey, privateSigningKey);
+                expect(Object.keys(granted.encryptedDeks)).toEqual(["alice", "bob", "carol"]);
+                expect(granted.ciphertext).toEqual(document.ciphertext);
+                expect(Object.keys(document.encryptedDeks)).toEqual(["alice", "bob"]);
+                expect(api.decryptSharedDocument(granted, "carol", carol.privateKey, aad)).toEqual(data);
+
+                const revoked = api.revokeAccess(granted, "alice");
+                expect(Object.keys(revoked.encryptedDeks)).toEqual(["bob", "carol"]);
+                expect(() => api.decryptSharedDocument(revoked, "alice", alice.privateKey, aad)).toThrow(
+                    expect.objectContaining({code: "UnknownRecipient", field: "recipientId"})
+                );
+                expect(api.decryptSharedDocument(revoked, "carol", carol.privateKey, aad)).toEqual(data);
+            });
+
+            test("should only let authors with access grant it", () => {
+                const document = api.createSharedDocument(data, {alice: alice.publicKey}, privateSigningKey);
+                expect(() => api.grantAccess(document, "carol", carol.publicKey, bob.privateKey, privateSigningKey)).toThrow(
+                    expect.objectContaining({code: "DecryptFailed", field: "authorPrivateKey"})
+                );
+                expect(() => api.revokeAccess(document, "bob")).toThrow(expect.objectContaining({code: "UnknownRecipient"}));
+            });
+
+            test("should treat __proto__ as an ordinary recipient id", () => {
+                // JSON.parse creates an own __proto__ property, which the assignment then updates
+                const recipients = JSON.parse('{"__proto__": null}');
+                recipients["__proto__"] = alice.publicKey;
+                const document = api.createSharedDocument(data, recipients, privateSigningKey);
+                expect(Object.keys(document.encryptedDeks)).toEqual(["__proto__"]);
+                expect(api.decryptSharedDocument(document, "__proto__", alice.privateKey)).toEqual(data);
+
+                const other = api.createSharedDocument(data, {alice: alice.publicKey}, privateSigningKey);
+                expect(() => api.decryptSharedDocument(other, "__proto__", alice.privateKey)).toThrow(expect.objectContaining({code: "UnknownRecipient"}));
+                const granted = api.grantAccess(other, "__proto__", bob.publicKey, alice.privateKey, privateSigningKey);
+                expect(Object.keys(granted.encryptedDeks)).toEqual(["alice", "__proto__"]);
+                expect(api.decryptSharedDocument(granted, "__proto__", bob.privateKey)).toEqual(data);
+                expect(Object.keys(api.revokeAccess(granted, "__proto__").encryptedDeks)).toEqual(["alice"]);
+            });
+
+            test("should reject invalid recipients and documents", () => {
+                expect(() => api.createSharedDocument(data, {}, privateSigningKey)).toThrow(expect.objectContaining({code: "EmptyArray", field: "recipients"}));
+                expect(() => api.createSharedDocument(data, {alice: {x: Buffer.alloc(32)}}, privateSigningKey)).toThrow(
+                    expect.objectContaining({code: "MissingField", field: "recipients.alice.y"})
+                );
+                expect(() => api.decryptSharedDocument({ciphertext: Buffer.alloc(40)}, "alice", alice.privateKey)).toThrow(
+                    expect.objectContaining({code: "MissingField", field: "document.encryptedDeks"})
+                );
+            });
+        });
+
+        describe("encryptDocument/decryptDocument", () => {
+            const header = {documentId: "my-document", segmentId: 22};
+            const data = Buffer.from("document contents");
+
+            // Build and read IronCore v2 documents with Node's own AES-GCM, independently of encryptDocument, to check the
+            // layout other IronCore SDKs use: version 2, the 2 byte header length, the JSON header, the IV and the
+            // ciphertext followed by the GCM tag. There's no "IRON" magic in version 2.
+            const encryptWithNode = (plaintext, headerJson, data) => {
+                const iv = crypto.randomBytes(12);
+                const cipher = crypto.createCipheriv("aes-256-gcm", api.deriveSymmetricKey(plaintext), iv);
+                const ciphertext = Buffer.concat([cipher.update(data), cipher.final(), cipher.getAuthTag()]);
+                const headerLength = Buffer.alloc(2);
+                headerLength.writeUInt16BE(headerJson.length);
+                return Buffer.concat([Buffer.from([2]), headerLength, headerJson, iv, ciphertext]);
+            };
+            const decryptWithNode = (plaintext, document) => {
+                expect(document[0]).toBe(2);
+                const headerLength = document.readUInt16BE(1);
+                const headerJson = document.slice(3, 3 + headerLength);
+                const iv = document.slice(3 + headerLength, 15 + headerLength);
+                const ciphertext = document.slice(15 + headerLength, document.length - 16);
+                const decipher = crypto.createDecipheriv("aes-256-gcm", api.deriveSymmetricKey(plaintext), iv);
+                decipher.setAuthTag(document.slice(document.length - 16));
+                return {header: JSON.parse(headerJson.toString()), data: Buffer.concat([decipher.update(ciphertext), decipher.final()])};
+            };
+
+            test("should roundtrip a document and write the IronCore v2 header", () => {
+                const plaintext = api.generatePlaintext();
+                const document = api.encryptDocument(plaintext, data, header);
+                const headerJson = Buffer.from('{"_did_":"my-document","_sid_":22}');
+                expect(document.slice(0, 3)).toEqual(Buffer.from([2, 0, headerJson.length]));
+                expect(document.slice(3, 3 + headerJson.length)).toEqual(headerJson);
+                expect(document).toHaveLength(3 + headerJson.length + 12 + data.length + 16);
+
+                expect(recrypt.parseDocumentHeader(document)).toEqual(header);
+                expect(api.decryptDocument(plaintext, document)).toEqual({header, data});
+                expect(decryptWithNode(plaintext, document)).toEqual({header: {_did_: "my-document", _sid_: 22}, data});
+            });
+
+            test("should decrypt a v2 document written by another client", () => {
+                const plaintext = api.generatePlaintext();
+                const headerJson = Buffer.from('{"_sid_":22,"_did_":"my-document","other":true}');
+                const otherDocument = encryptWithNode(plaintext, headerJson, data);
+
+                expect(recrypt.parseDocumentHeader(otherDocument)).toEqual(header);
+                expect(api.decryptDocument(plaintext, otherDocument)).toEqual({header, data});
+            });
+
+            test("should reject invalid headers and modified documents", () => {
+                const plaintext = api.generatePlaintext();
+                const document = api.encryptDocument(plaintext, data, header);
+
+                const badVersion = Buffer.from(document);
+                badVersion[0] = 1;
+                expect(() => api.decryptDocument(plaintext, badVersion)).toThrow(expect.objectContaining({code: "UnsupportedVersion"}));
+                const version3 = Buffer.concat([Buffer.from([3]), Buffer.from("IRON"), document.slice(1)]);
+                expect(() => recrypt.parseDocumentHeader(version3)).toThrow(expect.objectContaining({code: "UnsupportedVersion", field: "encryptedDocument"}));
+                const badJson = Buffer.from(document);
+                badJson[3] = 0;
+                expect(() => api.decryptDocument(plaintext, badJson)).toThrow(expect.objectContaining({code: "InvalidEncoding"}));
+                expect(() => recrypt.parseDocumentHeader(document.slice(0, 10))).toThrow(RangeError);
+
+                const modified = Buffer.from(document);
+                modified[modified.length - 1] ^= 1;
+                expect(() => api.decryptDocument(plaintext, modified)).toThrow(expect.objectContaining({code: "InvalidCiphertext"}));
+                expect(() => api.decryptDocument(api.generatePlaintext(), document)).toThrow(expect.objectContaining({code: "InvalidCiphertext"}));
+            });
+
+            test("should reject invalid header objects", () => {
+                const plaintext = api.generatePlaintext();
+                expect(() => api.encryptDocument(plaintext, data, {segmentId: 1})).toThrow(expect.objectContaining({code: "MissingField", field: "header.documentId"}));
+                expect(() => api.encryptDocument(plaintext, data, {documentId: "a", segmentId: 1.5})).toThrow(
+                    expect.objectContaining({code: "InvalidFieldType", field: "header.segmentId"})
+                );
+                expect(() => api.encryptDocument(plaintext, data, {documentId: "a".repeat(70000), segmentId: 1})).toThrow(RangeError);
+            });
+        });
+
+        describe("createStreamEncryptor/createStreamDecryptor", () => {
+            const segmentLength = 64 * 1024 + 16;
+            const data = Buffer.alloc(150 * 1024, "streamed data");
+
+            const encryptStream = (plaintext, chunks) => {
+                const encryptor = api.createStreamEncryptor(plaintext);
+                return Buffer.concat([...chunks.map((chunk) => encryptor.update(chunk)), encryptor.final()]);
+            };
+            const decryptStream = (plaintext, encrypted) => {
+                const decryptor = api.createStreamDecryptor(plaintext);
+                return Buffer.concat([decryptor.update(encrypted), decryptor.final()]);
+            };
+
+            test("should roundtrip data across multiple chunks regardless of how it is split", () => {
+                const plaintext = api.generatePlaintext();
+                const encrypted = encryptStream(plaintext, [data.slice(0, 10), data.slice(10, 100000), data.slice(100000)]);
+                expect(encrypted).toHaveLength(8 + 3 * segmentLength - (3 * 64 * 1024 - data.length));
+                expect(encrypted[0]).toEqual(1);
+
+                const decryptor = api.createStreamDecryptor(plaintext);
+                const decrypted = [];
+                for (let i = 0; i < encrypted.length; i += 1000) {
+                    decrypted.push(decryptor.update(encrypted.slice(i, i + 1000)));
+                }
+                decrypted.push(decryptor.final());
+                expect(Buffer.concat(decrypted)).toEqual(data);
+            });
+
+            test("should roundtrip empty data and data which fills a whole chunk", () => {
+                const plaintext = api.generatePlaintext();
+                const empty = encryptStream(plaintext, []);
+                expect(empty).toHaveLength(8 + 16);
+                expect(decryptStream(plaintext, empty)).toEqual(Buffer.alloc(0));
+
+                const oneChunk = encryptStream(plaintext, [data.slice(0, 64 * 1024)]);
+                expect(oneChunk).toHaveLength(8 + segmentLength);
+                expect(decryptStream(plaintext, oneChunk)).toEqual(data.slice(0, 64 * 1024));
+            });
+
+            test("should detect truncated, reordered and modified streams", () => {
+                const plaintext = api.generatePlaintext();
+                const encrypted = encryptStream(plaintext, [data]);
+                const invalidCiphertext = expect.objectContaining({code: "InvalidCiphertext"});
+
+                expect(() => decryptStream(plaintext, encrypted.slice(0, 8 + 2 * segmentLength))).toThrow(invalidCiphertext);
+                expect(() => decryptStream(plaintext, encrypted.slice(0, 4))).toThrow(invalidCiphertext);
+
+                const reordered = Buffer.concat([
+                    encrypted.slice(0, 8),
+                    encrypted.slice(8 + segmentLength, 8 + 2 * segmentLength),
+                    encrypted.slice(8, 8 + segmentLength),
+                    encrypted.slice(8 + 2 * segmentLength),
+                ]);
+                expect(() => decryptStream(plaintext, reordered)).toThrow(invalidCiphertext);
+
+                const modified = Buffer.from(encrypted);
+                modified[100] ^= 1;
+                expect(() => decryptStream(plaintext, modified)).toThrow(invalidCiphertext);
+                expect(() => decryptStream(api.generatePlaintext(), encrypted)).toThrow(invalidCiphertext);
+            });
+
+            test("should reject unknown versions and use after final", () => {
+                const plaintext = api.generatePlaintext();
+                const encrypted = encryptStream(plaintext, [data.slice(0, 100)]);
+                encrypted[0] = 2;
+                expect(() => decryptStream(plaintext, encrypted)).toThrow(expect.objectContaining({code: "UnsupportedVersion"}));
+
+                const encryptor = api.createStreamEncryptor(plaintext);
+                encryptor.final();
+                expect(() => encryptor.update(data)).toThrow(expect.objectContaining({code: "StreamFinished"}));
+                expect(() => encryptor.final()).toThrow(expect.objectContaining({code: "StreamFinished"}));
+            });
+        });
+
+        describe("exportPrivateKey/importPrivateKey", () => {
+            // Cheap KDF parameters so the tests stay fast.
+            const params = {memoryCost: 64, timeCost: 1, parallelism: 1};
+
+            test("should roundtrip recrypt private keys and signing keys", () => {
+                const keys = api.generateKeyPair();
+                const blob = api.exportPrivateKey(keys.privateKey, "correct horse", params);
+                expect(blob[0]).toEqual(1);
+                expect(blob).toHaveLength(31 + 64 + 12 + 32 + 16);
+                expect(api.importPrivateKey(blob, "correct horse")).toEqual({keyType: "recrypt", ...keys});
+
+                const signingKeys = api.generateEd25519KeyPair({handle: true});
+                const signingBlob = api.exportPrivateKey(signingKeys.privateKey, Buffer.from("passphrase"), params);
+                const imported = api.importPrivateKey(signingBlob, Buffer.from("passphrase"), {handle: true});
+                expect(imported.keyType).toEqual("ed25519");
+                expect(imported.publicKey).toEqual(signingKeys.publicKey);
+                expect(recrypt.signingKeyPairHandleToBytes(imported.privateKey)).toEqual(recrypt.signingKeyPairHandleToBytes(signingKeys.privateKey));
+            });
+
+            test("should record the KDF parameters and use the defaults", () => {
+                const keys = api.generateKeyPair({handle: true});
+                const blob = api.exportPrivateKey(keys.privateKey, "passphrase", {memoryCost: 128, timeCost: 2});
+                expect(blob.readUInt32BE(3)).toEqual(128);
+                expect(blob.readUInt32BE(7)).toEqual(2);
+                expect(blob.readUInt32BE(11)).toEqual(1);
+                expect(api.computePublicKey(api.importPrivateKey(blob, "passphrase", {handle: true}).privateKey)).toEqual(keys.publicKey);
+
+                expect(() => api.exportPrivateKey(keys.privateKey, "passphrase", {memoryCost: 1})).toThrow(
+                    expect.objectContaining({code: "InputWrongSize", field: "params"})
+                );
+                expect(() => api.exportPrivateKey(keys.privateKey, "passphrase", {timeCost: -1})).toThrow(
+                    expect.objectContaining({code: "InvalidFieldType", field: "params.timeCost"})
+                );
+            });
+
+            test("should reject wrong passphrases and modified blobs", () => {
+                const keys = api.generateKeyPair();
+                const blob = api.exportPrivateKey(keys.privateKey, "passphrase", params);
+                expect(() => api.importPrivateKey(blob, "wrong")).toThrow(expect.objectContaining({code: "InvalidCiphertext", field: "blob"}));
+
+                const modifiedParams = Buffer.from(blob);
+                modifiedParams.writeUInt32BE(65, 3);
+                expect(() => api.importPrivateKey(modifiedParams, "passphrase")).toThrow(expect.objectContaining({code: "InvalidCiphertext"}));
+                const modifiedPublicKey = Buffer.from(blob);
+                modifiedPublicKey[40] ^= 1;
+                expect(() => api.importPrivateKey(modifiedPublicKey, "passphrase")).toThrow(expect.objectContaining({code: "InvalidCiphertext"}));
+                const hugeMemory = Buffer.from(blob);
+                hugeMemory.writeUInt32BE(0xffffffff, 3);
+                expect(() => api.importPrivateKey(hugeMemory, "passphrase")).toThrow(expect.objectContaining({code: "InvalidEncoding"}));
+
+                expect(() => api.importPrivateKey(blob.slice(0, 50), "passphrase")).toThrow(RangeError);
+                const badVersion = Buffer.from(blob);
+                badVersion[0] = 2;
+                expect(() => api.importPrivateKey(badVersion, "passphrase")).toThrow(expect.objectContaining({code: "UnsupportedVersion"}));
+            });
+
+            test("should refuse KDF parameters above the import limits", () => {
+                const keys = api.generateKeyPair();
+                const blob = api.exportPrivateKey(keys.privateKey, "passphrase", params);
+                const tooMuchMemory = Buffer.from(blob);
+                tooMuchMemory.writeUInt32BE(256 * 1024 + 1, 3);
+                expect(() => api.importPrivateKey(tooMuchMemory, "passphrase")).toThrow(expect.objectContaining({code: "InvalidEncoding", field: "blob"}));
+                const tooManyPasses = Buffer.from(blob);
+                tooManyPasses.writeUInt32BE(11, 7);
+                expect(() => api.importPrivateKey(tooManyPasses, "passphrase")).toThrow(expect.objectContaining({code: "InvalidEncoding", field: "blob"}));
+                expect(() => api.exportPrivateKey(keys.privateKey, "passphrase", {timeCost: 11})).toThrow(
+                    expect.objectContaining({code: "InputWrongSize", field: "params"})
+                );
+            });
+
+            test("should import asynchronously", async () => {
+                const keys = api.generateKeyPair();
+                const blob = api.exportPrivateKey(keys.privateKey, "passphrase", params);
+                expect(await api.importPrivateKeyAsync(blob, "passphrase")).toEqual({keyType: "recrypt", ...keys});
+                const imported = await api.importPrivateKeyAsync(blob, Buffer.from("passphrase"), {handle: true});
+                expect(recrypt.privateKeyHandleToBytes(imported.privateKey)).toEqual(keys.privateKey);
+
+                await expect(api.importPrivateKeyAsync(blob, "wrong")).rejects.toThrow(expect.objectContaining({code: "InvalidCiphertext", field: "blob"}));
+                await expect(api.importPrivateKeyAsync(blob, 5)).rejects.toThrow(expect.objectContaining({code: "InvalidFieldType", field: "passphrase"}));
+            });
+        });
+
+        describe("derivePrivateKey/deriveKeyPairFromPassphrase", () => {
+            const params = {memoryCost: 64, timeCost: 1, parallelism: 1};
+
+            test("should derive the same private key from the same secret", () => {
+                const secret = api.generatePlaintext();
+                const privateKey = api.derivePrivateKey(secret);
+                expect(privateKey).toHaveLength(32);
+                expect(api.derivePrivateKey(secret)).toEqual(privateKey);
+                expect(api.derivePrivateKey(api.generatePlaintext())).not.toEqual(privateKey);
+                expect(recrypt.privateKeyHandleToBytes(api.derivePrivateKey(secret, {handle: true}))).toEqual(privateKey);
+                expect(() => api.derivePrivateKey(Buffer.alloc(32))).toThrow(expect.objectContaining({code: "InvalidBufferLength", field: "secretBytes"}));
+            });
+
+            test("should derive the same key pair from the same passphrase and salt", () => {
+                const salt = Buffer.from("0123456789abcdef");
+                const keys = api.deriveKeyPairFromPassphrase("correct horse", salt, params);
+                expect(api.computePublicKey(keys.privateKey)).toEqual(keys.publicKey);
+                expect(api.deriveKeyPairFromPassphrase(Buffer.from("correct horse"), salt, params)).toEqual(keys);
+                expect(api.deriveKeyPairFromPassphrase("correct horse", Buffer.from("fedcba9876543210"), params)).not.toEqual(keys);
+                expect(api.deriveKeyPairFromPassphrase("correct horse", salt, {...params, timeCost: 2})).not.toEqual(keys);
+
+                const handleKeys = api.deriveKeyPairFromPassphrase("correct horse", salt, params, {handle: true});
+                expect(handleKeys.publicKey).toEqual(keys.publicKey);
+                expect(recrypt.privateKeyHandleToBytes(handleKeys.privateKey)).toEqual(keys.privateKey);
+            });
+
+            test("should derive the same key pair asynchronously", async () => {
+                const salt = Buffer.from("0123456789abcdef");
+                const keys = api.deriveKeyPairFromPassphrase("correct horse", salt, params);
+                expect(await api.deriveKeyPairFromPassphraseAsync("correct horse", salt, params)).toEqual(keys);
+                const handleKeys = await api.deriveKeyPairFromPassphraseAsync("correct horse", salt, params, {handle: true});
+                expect(recrypt.privateKeyHandleToBytes(handleKeys.privateKey)).toEqual(keys.privateKey);
+
+                await expect(api.deriveKeyPairFromPassphraseAsync("passphrase", Buffer.alloc(7), params)).rejects.toThrow(
+                    expect.objectContaining({code: "InvalidBufferLength", field: "salt"})
+                );
+            });
+
+            test("should reject short salts and invalid parameters", () => {
+                expect(() => api.deriveKeyPairFromPassphrase("passphrase", Buffer.alloc(7), params)).toThrow(
+                    expect.objectContaining({code: "InvalidBufferLength", field: "salt"})
+                );
+                expect(() => api.deriveKeyPairFromPassphrase("passphrase", Buffer.alloc(16), {memoryCost: 1})).toThrow(
+                    expect.objectContaining({code: "InputWrongSize", field: "kdfParams"})
+                );
+            });
+        });
+
+        describe("privateKeyToMnemonic/mnemonicToPrivateKey", () => {
+            const vectorKey = Buffer.from("066dca1a2bb7e8a1db2832148ce9933eea0f3ac9548d793112d9a95c9407efad", "hex");
+            const vectorWords =
+                "all hour make first leader extend hole alien behind guard gospel lava path output census museum junior mass reopen famous sing advance salt reform";
+
+            test("should encode keys with the standard wordlist and checksum", () => {
+                expect(api.privateKeyToMnemonic(vectorKey)).toEqual(vectorWords);
+                expect(api.privateKeyToMnemonic(recrypt.privateKeyHandleFromBytes(vectorKey))).toEqual(vectorWords);
+                const keys = api.mnemonicToPrivateKey(vectorWords);
+                expect(keys.privateKey).toEqual(vectorKey);
+                expect(keys.publicKey).toEqual(api.computePublicKey(vectorKey));
+            });
+
+            test("should roundtrip generated keys and check the expected public key", () => {
+                const keys = api.generateKeyPair();
+                const words = api.privateKeyToMnemonic(keys.privateKey);
+                expect(words.split(" ")).toHaveLength(24);
+                expect(api.mnemonicToPrivateKey(words.toUpperCase().split(" "), keys.publicKey)).toEqual(keys);
+                const handleKeys = api.mnemonicToPrivateKey(`  ${words}\n`, recrypt.compressPublicKey(keys.publicKey), {handle: true});
+                expect(recrypt.privateKeyHandleToBytes(handleKeys.privateKey)).toEqual(keys.privateKey);
+
+                expect(() => api.mnemonicToPrivateKey(words, api.generateKeyPair().publicKey)).toThrow(
+                    expect.objectContaining({code: "PublicKeyMismatch", field: "expectedPublicKey"})
+                );
+            });
+
+            test("should reject mistyped mnemonics and keys outside the field", () => {
+                const words = vectorWords.split(" ");
+                expect(() => api.mnemonicToPrivateKey(words.slice(1))).toThrow(expect.objectContaining({code: "InvalidMnemonic", field: "words"}));
+                expect(() => api.mnemonicToPrivateKey([...words.slice(0, 3), "recrypt", ...words.slice(4)])).toThrow(
+                    expect.objectContaining({code: "InvalidMnemonic", field: "words[3]"})
+                );
+                expect(() => api.mnemonicToPrivateKey([...words.slice(0, 3), "abandon", ...words.slice(4)])).toThrow(
+                    expect.objectContaining({code: "InvalidChecksum", field: "words"})
+                );
+                expect(() => api.mnemonicToPrivateKey(`${"zoo ".repeat(23)}vote`)).toThrow(expect.objectContaining({code: "InvalidEncoding", field: "words"}));
+                expect(() => api.mnemonicToPrivateKey([1])).toThrow(expect.objectContaining({code: "InvalidFieldType", field: "words[0]"}));
+            });
+        });
+
+        describe("splitPrivateKey/combinePrivateKeyShares", () => {
+            test("should recover the key from any threshold of shares", () => {
+                const keys = api.generateKeyPair();
+                const shares = api.splitPrivateKey(keys.privateKey, 3, 5);
+                expect(shares).toHaveLength(5);
+                shares.forEach((share, i) => {
+                    expect(share).toHaveLength(103);
+                    expect(share[2]).toEqual(i + 1);
+                });
+                const recovered = api.combinePrivateKeyShares([shares[4], shares[0], shares[2]], keys.publicKey);
+                expect(recovered.publicKey).toEqual(keys.publicKey);
+                expect(api.computePublicKey(recovered.privateKey)).toEqual(keys.publicKey);
+                expect(api.combinePrivateKeyShares(shares.slice(1, 4)).publicKey).toEqual(keys.publicKey);
+                expect(api.combinePrivateKeyShares(shares).publicKey).toEqual(keys.publicKey);
+
+                const handleKeys = api.combinePrivateKeyShares(api.splitPrivateKey(recovered.privateKey, 2, 2), undefined, {handle: true});
+                expect(api.computePublicKey(handleKeys.privateKey)).toEqual(keys.publicKey);
+            });
+
+            test("should reject bad thresholds, corrupted shares and mixed splits", () => {
+                const keys = api.generateKeyPair({handle: true});
+                expect(() => api.splitPrivateKey(keys.privateKey, 1, 3)).toThrow(expect.objectContaining({code: "InputWrongSize", field: "threshold"}));
+                expect(() => api.splitPrivateKey(keys.privateKey, 4, 3)).toThrow(expect.objectContaining({code: "InputWrongSize", field: "threshold"}));
+                expect(() => api.splitPrivateKey(keys.privateKey, 2, 256)).toThrow(expect.objectContaining({code: "InputWrongSize", field: "shares"}));
+
+                const shares = api.splitPrivateKey(keys.privateKey, 2, 3);
+                expect(() => api.combinePrivateKeyShares([shares[0]])).toThrow(expect.objectContaining({code: "NotEnoughShares", field: "shares"}));
+                expect(() => api.combinePrivateKeyShares([shares[0], shares[0]])).toThrow(expect.objectContaining({code: "InvalidEncoding", field: "shares[1]"}));
+                expect(() => api.combinePrivateKeyShares([])).toThrow(expect.objectContaining({code: "EmptyArray", field: "shares"}));
+                const corrupted = Buffer.from(shares[1]);
+                corrupted[80] ^= 1;
+                expect(() => api.combinePrivateKeyShares([shares[0], corrupted])).toThrow(expect.objectContaining({code: "InvalidChecksum", field: "shares[1]"}));
+
+                const otherShares = api.splitPrivateKey(api.generateKeyPair().privateKey, 2, 3);
+                expect(() => api.combinePrivateKeyShares([shares[0], otherShares[1]])).toThrow(expect.objectContaining({code: "PublicKeyMismatch", field: "shares[1]"}));
+                expect(() => api.combinePrivateKeyShares(shares, api.generateKeyPair().publicKey)).toThrow(
+                    expect.objectContaining({code: "PublicKeyMismatch", field: "expectedPublicKey"})
+                );
+            });
+        });
+
+        describe("rotateUserPrivateKey", () => {
+            test("should add the augmentation factor to the private and public keys", () => {
+                const userKeys = api.generateKeyPair();
+                const rotation = api.rotateUserPrivateKey(userKeys.privateKey);
+                expect(Object.keys(rotation)).toEqual(["privateKey", "augmentationFactor", "publicKey"]);
+                expect(rotation.privateKey).not.toEqual(userKeys.privateKey);
+                expect(rotation.privateKey).toEqual(recrypt.addPrivateKeys(userKeys.privateKey, rotation.augmentationFactor));
+                expect(rotation.publicKey).toEqual(api.computePublicKey(rotation.privateKey));
+                expect(rotation.publicKey).toEqual(recrypt.augmentPublicKey256(userKeys.publicKey, api.computePublicKey(rotation.augmentationFactor)));
+
+                const handleRotation = api.rotateUserPrivateKey(recrypt.privateKeyHandleFromBytes(userKeys.privateKey), {handle: true});
+                expect(api.computePublicKey(handleRotation.privateKey)).toEqual(handleRotation.publicKey);
+                expect(recrypt.privateKeyHandleToBytes(handleRotation.augmentationFactor)).toHaveLength(32);
+            });
+
+            test("should keep outgoing transform keys working after applyRotationToTransformKeys", () => {
+                const plaintext = api.generatePlaintext();
+                const userKeys = api.generateKeyPair();
+                const deviceKeys = [api.generateKeyPair(), api.generateKeyPair()];
+                const transformKeys = deviceKeys.map((deviceKey) => api.generateTransformKey(userKeys.privateKey, deviceKey.publicKey, privateSigningKey));
+
+                const rotation = api.rotateUserPrivateKey(userKeys.privateKey);
+                const rotatedTransformKeys = recrypt.applyRotationToTransformKeys(transformKeys, rotation.augmentationFactor);
+                expect(rotatedTransformKeys).toHaveLength(2);
+                rotatedTransformKeys.forEach((rotatedTransformKey, i) => {
+                    expect(rotatedTransformKey).toEqual(recrypt.augmentTransformKey256(transformKeys[i], rotation.augmentationFactor));
+                    const encryptedValue = api.encrypt(plaintext, rotation.publicKey, privateSigningKey);
+                    const transformedValue = api.transform(encryptedValue, rotatedTransformKey, privateSigningKey);
+                    expect(api.decrypt(transformedValue, deviceKeys[i].privateKey)).toEqual(plaintext);
+                });
+
+                expect(() => recrypt.applyRotationToTransformKeys([transformKeys[0], {}], rotation.augmentationFactor)).toThrow(
+                    expect.objectContaining({code: "MissingField", field: "transformKeys[1].encryptedTempKey"})
+                );
+            });
+        });
+
+        describe("rotateGroupKey/rotateGroupKeyAsync", () => {
+            const checkRotation = (groupKeys, memberKeys, rotation) => {
+                expect(rotation.publicKey).toEqual(api.computePublicKey(rotation.privateKey));
+                expect(rotation.publicKey).toEqual(recrypt.augmentPublicKey256(groupKeys.publicKey, api.computePublicKey(rotation.augmentationFactor)));
+                expect(rotation.transformKeys).toBeArrayOfSize(memberKeys.length);
+                const plaintext = api.generatePlaintext();
+                const encryptedValue = api.encrypt(plaintext, rotation.publicKey, privateSigningKey);
+                rotation.transformKeys.forEach((transformKey, i) => {
+                    expect(transformKey.toPublicKey).toEqual(memberKeys[i].publicKey);
+                    const transformed = api.transform(encryptedValue, transformKey, privateSigningKey);
+                    expect(api.decrypt(transformed, memberKeys[i].privateKey)).toEqual(plaintext);
+                });
+            };
+
+            test.each([[false], [true]])("should re-key every member (parallel: %s)", (parallel) => {
+                const groupKeys = api.generateKeyPair();
+                const memberKeys = [api.generateKeyPair(), api.generateKeyPair(), api.generateKeyPair()];
+                const rotation = api.rotateGroupKey(
+                    groupKeys.privateKey,
+                    memberKeys.map((keys) => keys.publicKey),
+                    privateSigningKey,
+                    {parallel}
+                );
+                checkRotation(groupKeys, memberKeys, rotation);
+            });
+
+            test("should re-key every member asynchronously", async () => {
+                const groupKeys = api.generateKeyPair({handle: true});
+                const memberKeys = [api.generateKeyPair(), api.generateKeyPair()];
+                const rotation = await api.rotateGroupKeyAsync(
+                    groupKeys.privateKey,
+                    [memberKeys[0].publicKey, recrypt.compressPublicKey(memberKeys[1].publicKey)],
+                    privateSigningKey,
+                    {parallel: true, handle: true}
+                );
+                checkRotation(groupKeys, memberKeys, rotation);
+            });
+
+            test("should return per member errors without failing the rotation", async () => {
+                const groupKeys = api.generateKeyPair();
+                const memberKeys = api.generateKeyPair();
+                const badPublicKey = {x: memberKeys.publicKey.x, y: Buffer.alloc(32)};
+
+                const rotation = api.rotateGroupKey(groupKeys.privateKey, [badPublicKey, memberKeys.publicKey], privateSigningKey);
+                expect(rotation.transformKeys[0]).toBeInstanceOf(Error);
+                expect(rotation.transformKeys[0].field).toBe("memberPublicKeys[0]");
+                checkRotation(groupKeys, [memberKeys], {...rotation, transformKeys: rotation.transformKeys.slice(1)});
+
+                const asyncRotation = await api.rotateGroupKeyAsync(groupKeys.privateKey, [memberKeys.publicKey, {}], privateSigningKey);
+                expect(asyncRotation.transformKeys[1]).toBeInstanceOf(Error);
+                expect(asyncRotation.transformKeys[1].code).toBe("MissingField");
+                checkRotation(groupKeys, [memberKeys], {...asyncRotation, transformKeys: asyncRotation.transformKeys.slice(0, 1)});
+            });
+        });
+
+        describe("verifyEncryptedValue", () => {
+            test("should verify encrypted once and transformed values", () => {
+                const keys = [api.generateKeyPair(), api.generateKeyPair(), api.generateKeyPair()];
+                const encryptedValue = api.encrypt(api.generatePlaintext(), keys[0].publicKey, privateSigningKey);
+                expect(api.verifyEncryptedValue(encryptedValue)).toEqual({valid: true});
+                expect(api.verifyEncryptedValue(encryptedValue, publicSigningKey)).toEqual({valid: true});
+                expect(api.verifyEncryptedValue(encryptedValue, null)).toEqual({valid: true});
+
+                const transformed = api.transform(
+                    api.transform(encryptedValue, api.generateTransformKey(keys[0].privateKey, keys[1].publicKey, privateSigningKey), privateSigningKey),
+                    api.generateTransformKey(keys[1].privateKey, keys[2].publicKey, privateSigningKey),
+                    privateSigningKey
+                );
+                expect(transformed.transformBlocks).toBeArrayOfSize(2);
+                expect(api.verifyEncryptedValue(transformed, publicSigningKey)).toEqual({valid: true});
+            });
+
+            test("should report which check failed", () => {
+                const keys = api.generateKeyPair();
+                const otherSigningKeys = api.generateEd25519KeyPair();
+                const encryptedValue = api.encrypt(api.generatePlaintext(), keys.publicKey, privateSigningKey);
+
+                expect(api.verifyEncryptedValue(encryptedValue, otherSigningKeys.publicKey)).toEqual({
+                    valid: false,
+                    code: "PublicSigningKeyMismatch",
+                    field: "encryptedValue.publicSigningKey",
+                });
+                const invalidSignature = {valid: false, code: "InvalidEncryptedMessageSignature", field: "encryptedValue.signature"};
+                expect(api.verifyEncryptedValue({...encryptedValue, authHash: Buffer.alloc(32)})).toEqual(invalidSignature);
+                expect(api.verifyEncryptedValue({...encryptedValue, publicSigningKey: otherSigningKeys.publicKey})).toEqual(invalidSignature);
+                expect(() => api.verifyEncryptedValue(encryptedValue, Buffer.alloc(31))).toThrow(
+                    expect.objectContaining({code: "InvalidBufferLength", field: "expectedPublicSigningKey"})
+                );
+            });
+
+            test("should cover the transform blocks in the signature", () => {
+                const keys = [api.generateKeyPair(), api.generateKeyPair()];
+                const encryptedValue = api.encrypt(api.generatePlaintext(), keys[0].publicKey, privateSigningKey);
+                const transformed = api.transform(
+                    encryptedValue,
+                    api.generateTransformKey(keys[0].privateKey, keys[1].publicKey, privateSigningKey),
+                    privateSigningKey
+                );
+                const [block] = transformed.transformBlocks;
+                const tampered = {...transformed, transformBlocks: [{...block, randomTransformPublicKey: api.generateKeyPair().publicKey}]};
+                expect(api.verifyEncryptedValue(tampered)).toEqual({
+                    valid: false,
+                    code: "InvalidEncryptedMessageSignature",
+                    field: "encryptedValue.signature",
+                });
+                expect(() => api.decrypt(tampered, keys[1].privateKey)).toThrow(expect.objectContaining({code: "InvalidEncryptedMessageSignature"}));
+            });
+        });
+
+        describe("createAugmentedKeyPair", () => {
+            test("should round trip a value encrypted to the combined public key", () => {
+                const userKeys = api.generateKeyPair();
+                const deviceKeys = api.generateKeyPair();
+                const augmented = api.createAugmentedKeyPair({publicKey: userKeys.publicKey});
+                expect(augmented.combinedPublicKey).toEqual(
+                    recrypt.augmentPublicKey256(userKeys.publicKey, api.computePublicKey(augmented.augmentationPrivateKey))
+                );
+
+                const transformKey = api.generateAugmentedTransformKey(
+                    userKeys.privateKey,
+                    augmented.augmentationPrivateKey,
+                    deviceKeys.publicKey,
+                    privateSigningKey
+                );
+                const plaintext = api.generatePlaintext();
+                const encryptedValue = api.encrypt(plaintext, augmented.combinedPublicKey, privateSigningKey);
+                const transformed = api.transform(encryptedValue, transformKey, privateSigningKey);
+                expect(api.decrypt(transformed, deviceKeys.privateKey)).toEqual(plaintext);
+            });
+
+            test("should check the private key and return a handle when asked", () => {
+                const userKeys = api.generateKeyPair();
+                const augmented = api.createAugmentedKeyPair(userKeys, {handle: true});
+                expect(augmented.augmentationPrivateKey).not.toBeInstanceOf(Buffer);
+                expect(augmented.combinedPublicKey).toEqual(
+                    recrypt.augmentPublicKey256(userKeys.publicKey, api.computePublicKey(augmented.augmentationPrivateKey))
+                );
+
+                expect(() => api.createAugmentedKeyPair({publicKey: userKeys.publicKey, privateKey: api.generateKeyPair().privateKey})).toThrow(
+                    expect.objectContaining({code: "PublicKeyMismatch", field: "userKeyPair.publicKey"})
+                );
+                expect(() => api.createAugmentedKeyPair({privateKey: userKeys.privateKey})).toThrow(
+                    expect.objectContaining({code: "MissingField", field: "userKeyPair.publicKey"})
+                );
+            });
+
+            test("should verify signatures for the combined key only with the user's half", () => {
+                const userKeys = api.generateKeyPair();
+                const augmented = api.createAugmentedKeyPair(userKeys);
+                const message = Buffer.from("proof of possession");
+
+                const signature = api.signForAugmentedKey(userKeys.privateKey, augmented.combinedPublicKey, message);
+                expect(api.verifyAugmentedKeySignature(augmented.combinedPublicKey, augmented.augmentationPrivateKey, message, signature)).toBeTrue();
+                expect(
+                    api.verifyAugmentedKeySignature(augmented.combinedPublicKey, augmented.augmentationPrivateKey, Buffer.from("other message"), signature)
+                ).toBeFalse();
+                expect(
+                    api.verifyAugmentedKeySignature(augmented.combinedPublicKey, api.generateKeyPair().privateKey, message, signature)
+                ).toBeFalse();
+
+                const forged = api.signForAugmentedKey(api.generateKeyPair().privateKey, augmented.combinedPublicKey, message);
+                expect(api.verifyAugmentedKeySignature(augmented.combinedPublicKey, augmented.augmentationPrivateKey, message, forged)).toBeFalse();
+            });
+        });
+
         describe("Schnorr sign", () => {
             it("should sign the provided bytes and return the expected signature", () => {
                 const keys = api.generateKeyPair();
@@ -403,6 +1382,163 @@ describe("Recrypt-Node", () => {
         });
     });
 
+    describe("Api480", () => {
+        const api = new recrypt.Api480();
+
+        test("should generate keypairs of the expected length", () => {
+            const keypair = api.generateKeyPair();
+            expect(keypair.privateKey).toBeInstanceOf(Buffer);
+            expect(keypair.privateKey).toHaveLength(60);
+            expect(keypair.publicKey.x).toHaveLength(60);
+            expect(keypair.publicKey.y).toHaveLength(60);
+            expect(api.computePublicKey(keypair.privateKey)).toEqual(keypair.publicKey);
+        });
+
+        test("should generate plaintexts and symmetric keys of the expected length", () => {
+            const plaintext = api.generatePlaintext();
+            expect(plaintext).toHaveLength(720);
+            expect(api.deriveSymmetricKey(plaintext)).toHaveLength(32);
+        });
+
+        test("should roundtrip a signature with a generated ed25519 keypair", () => {
+            const keypair = api.generateEd25519KeyPair();
+            const signature = api.ed25519Sign(keypair.privateKey, Buffer.from("message to sign"));
+
+            expect(api.computeEd25519PublicKey(keypair.privateKey)).toEqual(keypair.publicKey);
+            expect(api.ed25519Verify(keypair.publicKey, Buffer.from("message to sign"), signature)).toBeTrue();
+        });
+
+        test("should roundtrip encrypt, transform and decrypt", () => {
+            const plaintext = api.generatePlaintext();
+            const fromKeys = api.generateKeyPair();
+            const toKeys = api.generateKeyPair();
+
+            const encryptedValue = api.encrypt(plaintext, fromKeys.publicKey, privateSigningKey);
+            expect(encryptedValue.encryptedMessage).toHaveLength(720);
+            expect(encryptedValue.ephemeralPublicKey.x).toHaveLength(60);
+            expect(api.decrypt(encryptedValue, fromKeys.privateKey)).toEqual(plaintext);
+
+            const transformKey = api.generateTransformKey(fromKeys.privateKey, toKeys.publicKey, privateSigningKey);
+            expect(transformKey.encryptedTempKey).toHaveLength(720);
+            expect(transformKey.hashedTempKey).toHaveLength(240);
+
+            const transformedValue = api.transform(encryptedValue, transformKey, privateSigningKey);
+            expect(transformedValue.transformBlocks).toHaveLength(1);
+            expect(transformedValue.transformBlocks[0].encryptedTempKey).toHaveLength(720);
+            expect(api.decrypt(transformedValue, toKeys.privateKey)).toEqual(plaintext);
+        });
+
+        test("should reject 256-bit values", () => {
+            const api256 = new recrypt.Api256();
+
+            expect(() => api.computePublicKey(api256.generateKeyPair().privateKey)).toThrow(
+                expect.objectContaining({code: "InvalidBufferLength", field: "privateKey"})
+            );
+            expect(() => api.encrypt(api256.generatePlaintext(), api.generateKeyPair().publicKey, privateSigningKey)).toThrow(RangeError);
+        });
+
+        test("should sign and verify Schnorr signatures", () => {
+            const userKeys = api.generateKeyPair();
+            const serverKeys = api.generateKeyPair();
+            const message = Buffer.from("message to sign");
+
+            const signature = api.schnorrSign(userKeys.privateKey, userKeys.publicKey, message);
+            expect(signature).toHaveLength(120);
+            expect(api.schnorrVerify(userKeys.publicKey, undefined, message, signature)).toBeTrue();
+            expect(api.schnorrVerify(serverKeys.publicKey, undefined, message, signature)).toBeFalse();
+
+            const augmentedPublicKey = recrypt.augmentPublicKey480(userKeys.publicKey, serverKeys.publicKey);
+            const augmentedSignature = api.schnorrSign(userKeys.privateKey, augmentedPublicKey, message);
+            expect(api.schnorrVerify(augmentedPublicKey, serverKeys.privateKey, message, augmentedSignature)).toBeTrue();
+        });
+    });
+
+    describe("invalid input errors", () => {
+        const api = new recrypt.Api256();
+
+        test("should throw a RangeError with the field path when a buffer is the wrong length", () => {
+            const keys = api.generateKeyPair();
+            const badPublicKey = {x: keys.publicKey.x, y: keys.publicKey.y.slice(1)};
+
+            expect(() => api.encrypt(api.generatePlaintext(), badPublicKey, privateSigningKey)).toThrow(RangeError);
+            expect(() => api.encrypt(api.generatePlaintext(), badPublicKey, privateSigningKey)).toThrow(
+                expect.objectContaining({code: "InvalidBufferLength", field: "toPublicKey.y"})
+            );
+        });
+
+        test("should throw a TypeError when a required field is missing", () => {
+            const keys = api.generateKeyPair();
+            const encryptedValue = api.encrypt(api.generatePlaintext(), keys.publicKey, privateSigningKey);
+            delete encryptedValue.authHash;
+
+            expect(() => api.decrypt(encryptedValue, keys.privateKey)).toThrow(TypeError);
+            expect(() => api.decrypt(encryptedValue, keys.privateKey)).toThrow(
+                expect.objectContaining({code: "MissingField", field: "encryptedValue.authHash"})
+            );
+        });
+
+        test("should include the transform block index in the field path", () => {
+            const groupKeys = api.generateKeyPair();
+            const userKeys = api.generateKeyPair();
+            const deviceKeys = api.generateKeyPair();
+            const groupToUser = api.generateTransformKey(groupKeys.privateKey, userKeys.publicKey, privateSigningKey);
+            const userToDevice = api.generateTransformKey(userKeys.privateKey, deviceKeys.publicKey, privateSigningKey);
+            const lvl1 = api.transform(api.encrypt(api.generatePlaintext(), groupKeys.publicKey, privateSigningKey), groupToUser, privateSigningKey);
+            const lvl2 = api.transform(lvl1, userToDevice, privateSigningKey);
+            lvl2.transformBlocks[1].publicKey.y = Buffer.alloc(31);
+
+            expect(() => api.decrypt(lvl2, deviceKeys.privateKey)).toThrow(
+                expect.objectContaining({code: "InvalidBufferLength", field: "encryptedValue.transformBlocks[1].publicKey.y"})
+            );
+        });
+
+        test("should throw a TypeError when a field is of the wrong type", () => {
+            const fromPrivateKey = api.generateKeyPair().privateKey;
+            const toPublicKey = api.generateKeyPair().publicKey;
+            const transformKey = api.generateTransformKey(fromPrivateKey, toPublicKey, privateSigningKey);
+            transformKey.hashedTempKey = "not a buffer";
+
+            expect(() => recrypt.transformKeyToBytes256(transformKey)).toThrow(TypeError);
+            expect(() => recrypt.transformKeyToBytes256(transformKey)).toThrow(
+                expect.objectContaining({code: "InvalidFieldType", field: "transformKey.hashedTempKey"})
+            );
+        });
+
+        test("should throw when the signing key pair is invalid", () => {
+            const badSigningKey = Buffer.concat([privateSigningKey.slice(0, 32), Buffer.alloc(32)]);
+
+            expect(() => api.computeEd25519PublicKey(badSigningKey)).toThrow(expect.objectContaining({code: "InvalidSigningKeyPair", field: "privateSigningKey"}));
+        });
+    });
+
+    describe("recrypt operation errors", () => {
+        const api = new recrypt.Api256();
+
+        test("should throw DecryptFailed when decrypting with the wrong private key", () => {
+            const keys = api.generateKeyPair();
+            const encryptedValue = api.encrypt(api.generatePlaintext(), keys.publicKey, privateSigningKey);
+
+            expect(() => api.decrypt(encryptedValue, api.generateKeyPair().privateKey)).toThrow(expect.objectContaining({code: "DecryptFailed"}));
+        });
+
+        test("should throw InvalidEncryptedMessageSignature when the encrypted value has been tampered with", () => {
+            const keys = api.generateKeyPair();
+            const encryptedValue = api.encrypt(api.generatePlaintext(), keys.publicKey, privateSigningKey);
+            encryptedValue.signature[0] ^= 1;
+
+            expect(() => api.decrypt(encryptedValue, keys.privateKey)).toThrow(expect.objectContaining({code: "InvalidEncryptedMessageSignature"}));
+        });
+
+        test("should throw InvalidPublicKey when a public key is not on the curve", () => {
+            const keys = api.generateKeyPair();
+            const offCurve = {x: keys.publicKey.x, y: Buffer.from(keys.publicKey.x)};
+
+            expect(() => api.encrypt(api.generatePlaintext(), offCurve, privateSigningKey)).toThrow(
+                expect.objectContaining({code: "InvalidPublicKey", field: "toPublicKey"})
+            );
+        });
+    });
+
     describe("augmentTransformKey256", () => {
         it("augments the provided transform key", () => {
             const api = new recrypt.Api256();
@@ -471,6 +1607,145 @@ describe("Recrypt-Node", () => {
         });
     });
 
+    describe("compressPublicKey/decompressPublicKey", () => {
+        const api = new recrypt.Api256();
+
+        it("roundtrips generated public keys", () => {
+            for (let i = 0; i < 10; i++) {
+                const publicKey = api.generateKeyPair().publicKey;
+                const compressed = recrypt.compressPublicKey(publicKey);
+                expect(compressed).toHaveLength(33);
+                expect([2, 3]).toContain(compressed[0]);
+                expect(recrypt.decompressPublicKey(compressed)).toEqual(publicKey);
+            }
+        });
+
+        it("accepts compressed public keys anywhere a public key is expected", () => {
+            const keys = api.generateKeyPair();
+            const toKeys = api.generateKeyPair();
+            const plaintext = api.generatePlaintext();
+
+            const encryptedValue = api.encrypt(plaintext, recrypt.compressPublicKey(keys.publicKey), privateSigningKey);
+            const transformKey = api.generateTransformKey(keys.privateKey, recrypt.compressPublicKey(toKeys.publicKey), privateSigningKey);
+            expect(transformKey.toPublicKey).toEqual(toKeys.publicKey);
+
+            const compressedEphemeral = {...encryptedValue, ephemeralPublicKey: recrypt.compressPublicKey(encryptedValue.ephemeralPublicKey)};
+            const transformedValue = api.transform(compressedEphemeral, transformKey, privateSigningKey);
+            expect(api.decrypt(transformedValue, toKeys.privateKey)).toEqual(plaintext);
+        });
+
+        it("rejects invalid compressed points", () => {
+            const compressed = recrypt.compressPublicKey(api.generateKeyPair().publicKey);
+            const badPrefix = Buffer.from(compressed);
+            badPrefix[0] = 4;
+            expect(() => recrypt.decompressPublicKey(badPrefix)).toThrow(
+                expect.objectContaining({code: "InvalidPublicKey", field: "compressedPublicKey"})
+            );
+            expect(() => recrypt.decompressPublicKey(Buffer.alloc(33, 255))).toThrow(expect.objectContaining({code: "InvalidPublicKey"}));
+            expect(() => api.encrypt(api.generatePlaintext(), compressed.slice(1), privateSigningKey)).toThrow(
+                expect.objectContaining({code: "InvalidBufferLength", field: "toPublicKey"})
+            );
+        });
+
+        it("roundtrips 480-bit public keys", () => {
+            const api480 = new recrypt.Api480();
+            const publicKey = api480.generateKeyPair().publicKey;
+            const compressed = recrypt.compressPublicKey480(publicKey);
+            expect(compressed).toHaveLength(61);
+            expect(recrypt.decompressPublicKey480(compressed)).toEqual(publicKey);
+        });
+    });
+
+    describe("encryptedValueToBytes/encryptedValueFromBytes", () => {
+        const api = new recrypt.Api256();
+        const keys = api.generateKeyPair();
+        const plaintext = api.generatePlaintext();
+        const encryptedValue = api.encrypt(plaintext, keys.publicKey, privateSigningKey);
+
+        it("roundtrips an encrypted once value", () => {
+            const bytes = recrypt.encryptedValueToBytes(encryptedValue);
+            expect(bytes).toBeInstanceOf(Buffer);
+            expect(bytes).toHaveLength(578);
+            expect(bytes[0]).toEqual(1);
+
+            const parsed = recrypt.encryptedValueFromBytes(bytes);
+            expect(parsed).toEqual(encryptedValue);
+            expect(api.decrypt(parsed, keys.privateKey)).toEqual(plaintext);
+        });
+
+        it("roundtrips a transformed value", () => {
+            const toKeys = api.generateKeyPair();
+            const transformKey = api.generateTransformKey(keys.privateKey, toKeys.publicKey, privateSigningKey);
+            const transformedValue = api.transform(encryptedValue, transformKey, privateSigningKey);
+
+            const bytes = recrypt.encryptedValueToBytes(transformedValue);
+            expect(bytes).toHaveLength(578 + 2 + 896);
+
+            const parsed = recrypt.encryptedValueFromBytes(bytes);
+            expect(parsed).toEqual(transformedValue);
+            expect(api.decrypt(parsed, toKeys.privateKey)).toEqual(plaintext);
+        });
+
+        it("rejects unknown versions", () => {
+            const bytes = recrypt.encryptedValueToBytes(encryptedValue);
+            bytes[0] = 2;
+            expect(() => recrypt.encryptedValueFromBytes(bytes)).toThrow(expect.objectContaining({code: "UnsupportedVersion", field: "bytes"}));
+        });
+
+        it("rejects unknown variants", () => {
+            const bytes = recrypt.encryptedValueToBytes(encryptedValue);
+            bytes[1] = 2;
+            expect(() => recrypt.encryptedValueFromBytes(bytes)).toThrow(expect.objectContaining({code: "InvalidEncoding"}));
+        });
+
+        it("rejects truncated input and trailing bytes", () => {
+            const bytes = recrypt.encryptedValueToBytes(encryptedValue);
+            expect(() => recrypt.encryptedValueFromBytes(bytes.slice(0, bytes.length - 1))).toThrow(RangeError);
+            expect(() => recrypt.encryptedValueFromBytes(Buffer.concat([bytes, Buffer.from([0])]))).toThrow(
+                expect.objectContaining({code: "InvalidBufferLength", field: "bytes"})
+            );
+        });
+    });
+
+    describe("transformKeyToBytes/transformKeyFromBytes", () => {
+        const api = new recrypt.Api256();
+        const fromKeys = api.generateKeyPair();
+        const toKeys = api.generateKeyPair();
+        const transformKey = api.generateTransformKey(fromKeys.privateKey, toKeys.publicKey, privateSigningKey);
+
+        it("roundtrips a transform key which can still be used", () => {
+            const bytes = recrypt.transformKeyToBytes(transformKey);
+            expect(bytes).toHaveLength(737);
+            expect(bytes).not.toEqual(recrypt.transformKeyToBytes256(transformKey));
+
+            const parsed = recrypt.transformKeyFromBytes(bytes);
+            expect(parsed).toEqual(transformKey);
+
+            const plaintext = api.generatePlaintext();
+            const encryptedValue = api.encrypt(plaintext, fromKeys.publicKey, privateSigningKey);
+            const transformedValue = api.transform(encryptedValue, parsed, privateSigningKey);
+            expect(api.decrypt(transformedValue, toKeys.privateKey)).toEqual(plaintext);
+        });
+
+        it("rejects corrupt points and hashed temp keys", () => {
+            const badPoint = recrypt.transformKeyToBytes(transformKey);
+            badPoint.fill(1, 1, 65);
+            expect(() => recrypt.transformKeyFromBytes(badPoint)).toThrow(expect.objectContaining({code: "InvalidPublicKey", field: "bytes"}));
+
+            const badHash = recrypt.transformKeyToBytes(transformKey);
+            badHash.fill(1, 513, 641);
+            expect(() => recrypt.transformKeyFromBytes(badHash)).toThrow(expect.objectContaining({code: "InvalidHashedValue"}));
+        });
+
+        it("rejects unknown versions and bad lengths", () => {
+            const bytes = recrypt.transformKeyToBytes(transformKey);
+            expect(() => recrypt.transformKeyFromBytes(Buffer.concat([bytes, Buffer.from([0])]))).toThrow(RangeError);
+            expect(() => recrypt.transformKeyFromBytes(bytes.slice(0, 700))).toThrow(RangeError);
+            bytes[0] = 9;
+            expect(() => recrypt.transformKeyFromBytes(bytes)).toThrow(expect.objectContaining({code: "UnsupportedVersion"}));
+        });
+    });
+
     describe("addPrivateKeys", () => {
         it("should add together the provided keys", () => {
             //prettier-ignore
@@ -496,4 +1771,36 @@ describe("Recrypt-Node", () => {
             );
         });
     });
+
+    describe("480-bit top level functions", () => {
+        const api = new recrypt.Api480();
+
+        it("augments the provided transform key", () => {
+            const fromPrivateKey = api.generateKeyPair().privateKey;
+            const toPublicKey = api.generateKeyPair().publicKey;
+            const augPrivateKey = api.generateKeyPair().privateKey;
+
+            const transformKey = api.generateTransformKey(fromPrivateKey, toPublicKey, privateSigningKey);
+            const augTransformKey = recrypt.augmentTransformKey480(transformKey, augPrivateKey);
+
+            expect(augTransformKey.toPublicKey).toEqual(toPublicKey);
+            expect(augTransformKey.hashedTempKey).toHaveLength(240);
+            expect(augTransformKey.hashedTempKey).not.toEqual(transformKey.hashedTempKey);
+        });
+
+        it("returns expected number of bytes for a transform key", () => {
+            const transformKey = api.generateTransformKey(api.generateKeyPair().privateKey, api.generateKeyPair().publicKey, privateSigningKey);
+
+            expect(recrypt.transformKeyToBytes480(transformKey)).toHaveLength(1232);
+        });
+
+        it("roundtrips adding and subtracting private keys", () => {
+            const key1 = api.generateKeyPair().privateKey;
+            const key2 = api.generateKeyPair().privateKey;
+
+            const sum = recrypt.addPrivateKeys480(key1, key2);
+            expect(sum).toHaveLength(60);
+            expect(recrypt.subtractPrivateKeys480(sum, key2)).toEqual(key1);
+        });
+    });
 });